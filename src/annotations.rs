@@ -0,0 +1,297 @@
+use std::collections::BTreeMap;
+
+use lopdf::{Dictionary, Document, Object, ObjectId};
+
+use crate::sheet::transform_point as transform;
+
+/// Annotation flag hiding an annotation from display and print
+const HIDDEN_FLAG: i64 = 1 << 1;
+
+/// Annotation entries holding pairs of page coordinates
+const COORDINATE_KEYS: [&[u8]; 3] = [b"QuadPoints", b"Vertices", b"L"];
+
+/// Annotation entries referring to other annotations of the same page
+const ANNOTATION_REFERENCE_KEYS: [&[u8]; 3] = [b"Popup", b"Parent", b"IRT"];
+
+/// Read a rectangle `[x0, y0, x1, y1]`, normalized so that x0 <= x1 and y0 <= y1
+fn rectangle(object: &Object) -> Option<[f32; 4]> {
+    let values: Vec<f32> = object
+        .as_array()
+        .ok()?
+        .iter()
+        .map(|value| value.as_float().ok())
+        .collect::<Option<_>>()?;
+    let &[x0, y0, x1, y1] = values.as_slice() else {
+        return None;
+    };
+    Some([x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1)])
+}
+
+/// Bounding box of a rectangle moved by a matrix
+fn transform_rectangle(matrix: [f32; 6], [x0, y0, x1, y1]: [f32; 4]) -> [f32; 4] {
+    let corners = [(x0, y0), (x1, y0), (x0, y1), (x1, y1)].map(|(x, y)| transform(matrix, x, y));
+    corners.iter().fold(
+        [f32::MAX, f32::MAX, f32::MIN, f32::MIN],
+        |[x0, y0, x1, y1], &(x, y)| [x0.min(x), y0.min(y), x1.max(x), y1.max(y)],
+    )
+}
+
+/// Move an array of x, y coordinate pairs by a matrix
+fn transform_coordinates(matrix: [f32; 6], coordinates: &[Object]) -> Option<Object> {
+    let values: Vec<f32> = coordinates
+        .iter()
+        .map(|value| value.as_float().ok())
+        .collect::<Option<_>>()?;
+    let moved = values
+        .chunks_exact(2)
+        .flat_map(|pair| {
+            let (x, y) = transform(matrix, pair[0], pair[1]);
+            [x.into(), y.into()]
+        })
+        .collect();
+    Some(Object::Array(moved))
+}
+
+/// Annotations of a page, with their object id when they are not inline
+fn page_annotations(doc: &Document, page_id: ObjectId) -> Vec<(Option<ObjectId>, Dictionary)> {
+    let Ok(annots) = doc
+        .get_dictionary(page_id)
+        .and_then(|page| page.get(b"Annots"))
+        .and_then(|annots| doc.dereference(annots))
+        .and_then(|(_, annots)| annots.as_array())
+    else {
+        return Vec::new();
+    };
+    annots
+        .iter()
+        .filter_map(|annot| match annot {
+            Object::Reference(id) => Some((Some(*id), doc.get_dictionary(*id).ok()?.clone())),
+            Object::Dictionary(dict) => Some((None, dict.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Copy the annotations of a page placed on a sheet, moving their coordinates
+/// by the placement matrix. Annotations entirely outside of `clip` are left out, and so
+/// are form field widgets, which belong to the document's interactive form.
+/// Returns references to the copies, to be listed in the sheet's /Annots.
+pub fn place_annotations(
+    doc: &mut Document,
+    page_id: ObjectId,
+    sheet_id: ObjectId,
+    matrix: [f32; 6],
+    clip: Option<[f32; 4]>,
+) -> Vec<Object> {
+    let mut placed: Vec<(Option<ObjectId>, Dictionary)> = Vec::new();
+    for (annot_id, mut annot) in page_annotations(doc, page_id) {
+        if annot.get(b"Subtype").and_then(Object::as_name).ok() == Some(b"Widget") {
+            continue;
+        }
+        let Some(rect) = annot.get(b"Rect").ok().and_then(rectangle) else {
+            continue;
+        };
+        let rect = transform_rectangle(matrix, rect);
+        if let Some([x0, y0, x1, y1]) = clip {
+            if rect[2] < x0 || rect[0] > x1 || rect[3] < y0 || rect[1] > y1 {
+                continue;
+            }
+        }
+        annot.set("Rect", Object::Array(rect.map(Object::from).to_vec()));
+        for key in COORDINATE_KEYS {
+            let moved = annot
+                .get(key)
+                .and_then(Object::as_array)
+                .ok()
+                .and_then(|coordinates| transform_coordinates(matrix, coordinates));
+            if let Some(moved) = moved {
+                annot.set(key, moved);
+            }
+        }
+        let ink = annot
+            .get(b"InkList")
+            .and_then(Object::as_array)
+            .ok()
+            .map(|paths| {
+                paths
+                    .iter()
+                    .filter_map(|path| transform_coordinates(matrix, path.as_array().ok()?))
+                    .collect::<Vec<_>>()
+            });
+        if let Some(ink) = ink {
+            annot.set("InkList", Object::Array(ink));
+        }
+        annot.set("P", Object::Reference(sheet_id));
+        // The structure tree still refers to the source page
+        annot.remove(b"StructParent");
+        placed.push((annot_id, annot));
+    }
+
+    // Copies refer to each other, a source page may be placed several times
+    let copies: BTreeMap<ObjectId, ObjectId> = placed
+        .iter()
+        .filter_map(|(annot_id, _)| Some((annot_id.as_ref().copied()?, doc.new_object_id())))
+        .collect();
+    let mut annots = Vec::with_capacity(placed.len());
+    for (annot_id, mut annot) in placed {
+        for key in ANNOTATION_REFERENCE_KEYS {
+            let Ok(referenced) = annot.get(key).and_then(Object::as_reference) else {
+                continue;
+            };
+            match copies.get(&referenced) {
+                Some(&copy_id) => annot.set(key, Object::Reference(copy_id)),
+                None => {
+                    annot.remove(key);
+                }
+            }
+        }
+        let copy_id = match annot_id.and_then(|annot_id| copies.get(&annot_id)) {
+            Some(&copy_id) => copy_id,
+            None => doc.new_object_id(),
+        };
+        doc.objects.insert(copy_id, Object::Dictionary(annot));
+        annots.push(Object::Reference(copy_id));
+    }
+    annots
+}
+
+/// Normal appearances of the visible annotations of a page, as Form XObjects
+/// with the matrix drawing each one at its rectangle in page space
+pub fn annotation_appearances(doc: &mut Document, page_id: ObjectId) -> Vec<(ObjectId, [f32; 6])> {
+    let mut appearances = Vec::new();
+    for (_, annot) in page_annotations(doc, page_id) {
+        let flags = annot.get(b"F").and_then(Object::as_i64).unwrap_or(0);
+        let is_popup = annot.get(b"Subtype").and_then(Object::as_name).ok() == Some(b"Popup");
+        if flags & HIDDEN_FLAG != 0 || is_popup {
+            continue;
+        }
+        let Some(rect) = annot.get(b"Rect").ok().and_then(rectangle) else {
+            continue;
+        };
+
+        // The normal appearance, or the one of the annotation's state
+        let normal = annot
+            .get(b"AP")
+            .and_then(|ap| doc.dereference(ap))
+            .and_then(|(_, ap)| ap.as_dict())
+            .and_then(|ap| ap.get(b"N"))
+            .ok();
+        let states = normal
+            .and_then(|normal| doc.dereference(normal).ok())
+            .and_then(|(_, normal)| normal.as_dict().ok());
+        let appearance = match states {
+            Some(states) => annot
+                .get(b"AS")
+                .and_then(Object::as_name)
+                .and_then(|state| states.get(state))
+                .ok(),
+            None => normal,
+        };
+        let appearance_id = match appearance {
+            Some(&Object::Reference(id)) => id,
+            Some(Object::Stream(stream)) => {
+                let stream = stream.clone();
+                doc.add_object(stream)
+            }
+            _ => continue,
+        };
+        let Ok(Object::Stream(stream)) = doc.get_object_mut(appearance_id) else {
+            continue;
+        };
+        let Some(bbox) = stream.dict.get(b"BBox").ok().and_then(rectangle) else {
+            continue;
+        };
+        let form_matrix = stream
+            .dict
+            .get(b"Matrix")
+            .and_then(Object::as_array)
+            .ok()
+            .and_then(|values| {
+                let values: Vec<f32> = values
+                    .iter()
+                    .map(|v| v.as_float().ok())
+                    .collect::<Option<_>>()?;
+                values.try_into().ok()
+            })
+            .unwrap_or([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+        // Appearance streams may leave out the Form XObject type
+        stream.dict.set("Type", Object::Name(b"XObject".to_vec()));
+        stream.dict.set("Subtype", Object::Name(b"Form".to_vec()));
+
+        // Fit the transformed bounding box to the rectangle
+        let [bx0, by0, bx1, by1] = transform_rectangle(form_matrix, bbox);
+        if bx1 <= bx0 || by1 <= by0 {
+            continue;
+        }
+        let [rx0, ry0, rx1, ry1] = rect;
+        let (sx, sy) = ((rx1 - rx0) / (bx1 - bx0), (ry1 - ry0) / (by1 - by0));
+        appearances.push((
+            appearance_id,
+            [sx, 0.0, 0.0, sy, rx0 - sx * bx0, ry0 - sy * by0],
+        ));
+    }
+    appearances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transform_rectangle() {
+        // Quarter turn clockwise, then scaled by half
+        let matrix = [0.0, -0.5, 0.5, 0.0, 10.0, 100.0];
+        assert_eq!(
+            transform_rectangle(matrix, [0.0, 0.0, 40.0, 20.0]),
+            [10.0, 80.0, 20.0, 100.0]
+        );
+        assert_eq!(
+            rectangle(&Object::Array(vec![
+                30.into(),
+                40.into(),
+                10.into(),
+                20.into()
+            ])),
+            Some([10.0, 20.0, 30.0, 40.0])
+        );
+    }
+
+    #[test]
+    fn test_place_annotations_skips_widgets() {
+        let mut doc = Document::with_version("1.5");
+        let annotation = |subtype: &str| {
+            let mut annot = Dictionary::new();
+            annot.set("Subtype", Object::Name(subtype.as_bytes().to_vec()));
+            annot.set(
+                "Rect",
+                Object::Array(vec![0.into(), 0.into(), 10.into(), 10.into()]),
+            );
+            annot
+        };
+        let field_id = doc.add_object(Dictionary::new());
+        let mut widget = annotation("Widget");
+        widget.set("Parent", Object::Reference(field_id));
+        let widget_id = doc.add_object(widget);
+        let link_id = doc.add_object(annotation("Link"));
+        let mut page = Dictionary::new();
+        page.set("Type", Object::Name(b"Page".to_vec()));
+        page.set(
+            "Annots",
+            Object::Array(vec![widget_id.into(), link_id.into()]),
+        );
+        let page_id = doc.add_object(page);
+        let sheet_id = doc.new_object_id();
+
+        let matrix = [1.0, 0.0, 0.0, 1.0, 5.0, 0.0];
+        let placed = place_annotations(&mut doc, page_id, sheet_id, matrix, None);
+        assert_eq!(placed.len(), 1);
+        let copy = doc
+            .get_dictionary(placed[0].as_reference().unwrap())
+            .unwrap();
+        assert_eq!(copy.get(b"Subtype").unwrap().as_name().unwrap(), b"Link");
+        assert_eq!(
+            rectangle(copy.get(b"Rect").unwrap()),
+            Some([5.0, 0.0, 15.0, 10.0])
+        );
+    }
+}
@@ -1,7 +1,13 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, str::FromStr};
 
 use clap::{Parser, Subcommand, ValueEnum};
 
+/// Points per millimetre
+pub const POINTS_PER_MM: f32 = 72.0 / 25.4;
+
+/// Points per inch
+pub const POINTS_PER_INCH: f32 = 72.0;
+
 /// Flip type
 #[derive(Copy, Clone, Debug, ValueEnum)]
 pub enum FlipType {
@@ -36,7 +42,7 @@ impl FlipType {
 }
 
 /// Output odd or even pages
-#[derive(Copy, Clone, Debug, ValueEnum)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
 pub enum OddEven {
     /// Odd pages
     #[value(name = "odd")]
@@ -48,7 +54,7 @@ pub enum OddEven {
 
 /// Defines booklet imposition layout type.
 /// This enum specifies the total number of booklet pages placed on each physical sheet (front and back).
-#[derive(Copy, Clone, Debug, ValueEnum)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
 pub enum LayoutType {
     /// Place 4 booklet pages on each physical sheet (2 pages per side).
     /// Suitable for printing A5 booklets on A4 paper.
@@ -58,14 +64,460 @@ pub enum LayoutType {
     /// Suitable for printing A6 booklets on A4 paper or A5 booklets on A3 paper.
     #[value(name = "four-up")]
     FourUp,
+    /// Place 8 booklet pages on one side of a single sheet, folded and cut into a mini-zine.
+    /// The top row is printed upside down, and cut and fold marks are drawn on the sheet.
+    #[value(name = "mini-zine")]
+    MiniZine,
+}
+
+impl LayoutType {
+    /// Number of booklet pages placed on a physical sheet, front and back
+    pub fn pages_per_sheet(&self) -> u32 {
+        match self {
+            LayoutType::TwoUp => 4,
+            LayoutType::FourUp => 8,
+            LayoutType::MiniZine => 8,
+        }
+    }
+
+    /// Number of booklet pages placed on one side of a physical sheet
+    pub fn pages_per_side(&self) -> usize {
+        match self {
+            LayoutType::TwoUp => 2,
+            LayoutType::FourUp => 4,
+            LayoutType::MiniZine => 8,
+        }
+    }
+
+    /// Number of booklet pages placed in one row of a sheet side
+    pub fn pages_per_row(&self) -> usize {
+        match self {
+            LayoutType::TwoUp | LayoutType::FourUp => 2,
+            LayoutType::MiniZine => 4,
+        }
+    }
+}
+
+/// Booklet binding edge.
+/// Determines on which side of the folded booklet the spine ends up.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Binding {
+    /// Left-to-right reading, spine on the left
+    #[value(name = "ltr", alias = "left")]
+    Ltr,
+    /// Right-to-left reading, spine on the right (manga, Arabic, Hebrew)
+    #[value(name = "rtl", alias = "right")]
+    Rtl,
+    /// Spine on the top edge (calendars, flip-charts).
+    /// Pages are stacked vertically and back sides are rotated 180°,
+    /// so sheets are printed with the usual long-edge duplex setting.
+    #[value(name = "top")]
+    Top,
+}
+
+/// Booklet binding method.
+/// Determines how printed sheets are gathered into the finished book.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum BindingMethod {
+    /// Sheets are nested inside each other and stapled on the fold
+    #[value(name = "saddle")]
+    Saddle,
+    /// Every sheet is folded on its own and the folded sheets are stacked, then glued
+    #[value(name = "perfect")]
+    Perfect,
+    /// Sheets are cut into single leaves and the stacks are put on top of each other
+    #[value(name = "cut-stack")]
+    CutStack,
+}
+
+/// Brochure fold.
+/// Determines the number of panels of a brochure, their widths and their order on the sheet.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum FoldType {
+    /// Letter fold: 3 panels per side rolled inwards, the tuck-in panel is narrower
+    #[value(name = "tri-fold", alias = "roll-fold")]
+    TriFold,
+    /// Accordion fold: 3 equal panels per side folded back and forth
+    #[value(name = "z-fold")]
+    ZFold,
+    /// Gate fold: 4 panels per side, the two narrower outer panels close over the center
+    #[value(name = "gate-fold")]
+    GateFold,
+}
+
+impl FoldType {
+    /// Number of brochure pages placed on a physical sheet, front and back
+    pub fn panels(&self) -> u32 {
+        self.panels_per_side() as u32 * 2
+    }
+
+    /// Number of brochure pages placed on one side of a physical sheet
+    pub fn panels_per_side(&self) -> usize {
+        match self {
+            FoldType::TriFold | FoldType::ZFold => 3,
+            FoldType::GateFold => 4,
+        }
+    }
+}
+
+/// How source pages fill the slots of a grid
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum GridMode {
+    /// Every source page fills a whole sheet (business cards, labels)
+    #[value(name = "repeat")]
+    Repeat,
+    /// Consecutive source pages fill the slots one after the other (tickets, flashcards)
+    #[value(name = "sequence")]
+    Sequence,
+}
+
+/// Page a merged document starts on
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum DocumentStart {
+    /// Directly after the previous document
+    #[value(name = "any")]
+    Any,
+    /// On an odd (recto) page
+    #[value(name = "odd")]
+    Odd,
+    /// On an even (verso) page
+    #[value(name = "even")]
+    Even,
+}
+
+/// What happens to the annotations of pages placed on sheets
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum AnnotationMode {
+    /// Copy them onto the sheet, over the placed page
+    #[value(name = "keep")]
+    Keep,
+    /// Draw their appearance into the sheet content
+    #[value(name = "flatten")]
+    Flatten,
+    /// Leave them out
+    #[value(name = "drop")]
+    Drop,
+}
+
+/// Duplex printing of step-and-repeat sheets
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Duplex {
+    /// Single-sided, every page is a front
+    #[value(name = "none")]
+    None,
+    /// Odd pages are fronts and even pages are backs, sheet flipped on its long edge
+    #[value(name = "long-edge")]
+    LongEdge,
+    /// Odd pages are fronts and even pages are backs, sheet flipped on its short edge
+    #[value(name = "short-edge")]
+    ShortEdge,
+}
+
+/// Physical paper size in points.
+///
+/// Parsed from a name (`a3`, `a4`, `a5`, `a6`, `letter`, `legal`, `tabloid`), optionally
+/// followed by `-landscape`, or from explicit dimensions such as `210x297mm`, `8.5x11in`
+/// or `612x792pt` (millimetres when the unit is omitted).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PaperSize {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl PaperSize {
+    /// Create paper size from dimensions in millimetres
+    pub fn from_mm(width: f32, height: f32) -> Self {
+        Self {
+            width: width * POINTS_PER_MM,
+            height: height * POINTS_PER_MM,
+        }
+    }
+
+    /// Return the same paper turned to landscape orientation
+    pub fn landscape(self) -> Self {
+        Self {
+            width: self.width.max(self.height),
+            height: self.width.min(self.height),
+        }
+    }
+}
+
+/// Parse a length that must not be negative, such as a gutter or margin
+fn non_negative_length(s: &str) -> Result<f32, String> {
+    let length: f32 = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{}' is not a number", s))?;
+    if length.is_finite() && length >= 0.0 {
+        Ok(length)
+    } else {
+        Err(format!("Length must not be negative, got {}", s))
+    }
+}
+
+/// Parse a number that must be greater than zero, such as a paper thickness
+fn positive_number(s: &str) -> Result<f32, String> {
+    let number: f32 = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{}' is not a number", s))?;
+    if number.is_finite() && number > 0.0 {
+        Ok(number)
+    } else {
+        Err(format!("Number must be positive, got {}", s))
+    }
+}
+
+impl FromStr for PaperSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim().to_ascii_lowercase();
+        let (name, landscape) = match value.strip_suffix("-landscape") {
+            Some(name) => (name, true),
+            None => (value.as_str(), false),
+        };
+
+        let size = match name {
+            "a3" => PaperSize::from_mm(297.0, 420.0),
+            "a4" => PaperSize::from_mm(210.0, 297.0),
+            "a5" => PaperSize::from_mm(148.0, 210.0),
+            "a6" => PaperSize::from_mm(105.0, 148.0),
+            "letter" => PaperSize {
+                width: 8.5 * POINTS_PER_INCH,
+                height: 11.0 * POINTS_PER_INCH,
+            },
+            "legal" => PaperSize {
+                width: 8.5 * POINTS_PER_INCH,
+                height: 14.0 * POINTS_PER_INCH,
+            },
+            "tabloid" => PaperSize {
+                width: 11.0 * POINTS_PER_INCH,
+                height: 17.0 * POINTS_PER_INCH,
+            },
+            _ => {
+                let (dimensions, scale) = if let Some(d) = name.strip_suffix("mm") {
+                    (d, POINTS_PER_MM)
+                } else if let Some(d) = name.strip_suffix("in") {
+                    (d, POINTS_PER_INCH)
+                } else if let Some(d) = name.strip_suffix("pt") {
+                    (d, 1.0)
+                } else {
+                    (name, POINTS_PER_MM)
+                };
+                let parse = |v: &str| v.trim().parse::<f32>().ok().filter(|v| *v > 0.0);
+                match dimensions
+                    .split_once('x')
+                    .map(|(w, h)| (parse(w), parse(h)))
+                {
+                    Some((Some(width), Some(height))) => PaperSize {
+                        width: width * scale,
+                        height: height * scale,
+                    },
+                    _ => return Err(format!("unknown paper size: {}", s)),
+                }
+            }
+        };
+
+        Ok(if landscape { size.landscape() } else { size })
+    }
+}
+
+/// Number of sheets of the signatures of a saddle-stitched book
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SignatureSize {
+    /// Signatures of this many sheets, the last one may be smaller
+    Sheets(u32),
+    /// As few signatures as possible within the saddle-stitch sheet limit, sized evenly
+    Auto,
+}
+
+impl FromStr for SignatureSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(SignatureSize::Auto),
+            sheets => match sheets.parse::<u32>() {
+                Ok(sheets) if sheets > 0 => Ok(SignatureSize::Sheets(sheets)),
+                _ => Err(format!("invalid signature size: {}", s)),
+            },
+        }
+    }
+}
+
+/// Where blank pages padding a booklet to whole sheets are inserted
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BlankPosition {
+    /// After the last page
+    End,
+    /// Before the last page, which stays the back cover
+    BeforeLast,
+    /// After the first page, which stays the front cover
+    AfterFirst,
+    /// One blank after each listed page (0 is before the first page), used in order.
+    /// Blanks not covered by the list go to the end.
+    After(Vec<u32>),
+}
+
+impl FromStr for BlankPosition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "end" => Ok(BlankPosition::End),
+            "before-last" => Ok(BlankPosition::BeforeLast),
+            "after-first" => Ok(BlankPosition::AfterFirst),
+            list => list
+                .split(',')
+                .map(|page| page.trim().parse::<u32>())
+                .collect::<Result<Vec<u32>, _>>()
+                .map(BlankPosition::After)
+                .map_err(|_| format!("unknown blank position: {}", s)),
+        }
+    }
+}
+
+/// Page of a page selection
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PageRef {
+    /// Page number counted from the first page, starting at 1
+    Number(u32),
+    /// Page number counted from the last page, `r1` is the last page
+    Reverse(u32),
+    /// Last page
+    End,
+}
+
+impl PageRef {
+    /// Resolve to a page number of a document with `total_pages` pages
+    fn resolve(&self, total_pages: u32) -> Option<u32> {
+        let page = match *self {
+            PageRef::Number(page) => page,
+            PageRef::Reverse(page) => (total_pages + 1).checked_sub(page)?,
+            PageRef::End => total_pages,
+        };
+        (1..=total_pages).contains(&page).then_some(page)
+    }
+}
+
+impl FromStr for PageRef {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |v: &str| v.parse::<u32>().ok().filter(|page| *page > 0);
+        match s {
+            "end" => Some(PageRef::End),
+            _ => match s.strip_prefix('r') {
+                Some(page) => parse(page).map(PageRef::Reverse),
+                None => parse(s).map(PageRef::Number),
+            },
+        }
+        .ok_or_else(|| format!("invalid page: {}", s))
+    }
+}
+
+/// Page range of a page selection, descending when `from` comes after `to`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PageRange {
+    pub from: PageRef,
+    pub to: PageRef,
+    /// Keep only odd or even page numbers of the range
+    pub parity: Option<OddEven>,
+}
+
+/// Ordered page selection, written as comma-separated ranges:
+/// `N`, `N-M` (descending when `N` > `M`), `end` for the last page, `rN` for the N-th page
+/// from the end, and `even` or `odd` alone or after a range (`1-10odd`).
+///
+/// # Example
+/// ```
+/// use bookify_rs::args::PageSelection;
+///
+/// let selection: PageSelection = "1-2,r1,5-end,even".parse().unwrap();
+/// assert_eq!(selection.resolve(6).unwrap(), vec![1, 2, 6, 5, 6, 2, 4, 6]);
+/// let selection: PageSelection = "r3-r1,2-1".parse().unwrap();
+/// assert_eq!(selection.resolve(10).unwrap(), vec![8, 9, 10, 2, 1]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PageSelection(pub Vec<PageRange>);
+
+impl PageSelection {
+    /// Resolve the selection against a document with `total_pages` pages
+    pub fn resolve(&self, total_pages: u32) -> Result<Vec<u32>, String> {
+        let mut pages = Vec::new();
+        for range in &self.0 {
+            let resolve = |page: PageRef| {
+                page.resolve(total_pages).ok_or_else(|| {
+                    format!(
+                        "page {:?} is outside the {}-page document",
+                        page, total_pages
+                    )
+                })
+            };
+            let (from, to) = (resolve(range.from)?, resolve(range.to)?);
+            let numbers: Box<dyn Iterator<Item = u32>> = if from <= to {
+                Box::new(from..=to)
+            } else {
+                Box::new((to..=from).rev())
+            };
+            pages.extend(numbers.filter(|page| match range.parity {
+                Some(OddEven::Odd) => page % 2 == 1,
+                Some(OddEven::Even) => page % 2 == 0,
+                None => true,
+            }));
+        }
+        Ok(pages)
+    }
+}
+
+impl FromStr for PageSelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ranges = Vec::new();
+        for item in s.split(',') {
+            let item = item.trim().to_ascii_lowercase();
+            let (item, parity) = if let Some(range) = item.strip_suffix("even") {
+                (range, Some(OddEven::Even))
+            } else if let Some(range) = item.strip_suffix("odd") {
+                (range, Some(OddEven::Odd))
+            } else {
+                (item.as_str(), None)
+            };
+
+            let (from, to) = match item.split_once('-') {
+                _ if item.is_empty() && parity.is_some() => (PageRef::Number(1), PageRef::End),
+                Some((from, to)) => (from.parse()?, to.parse()?),
+                None => {
+                    let page = item.parse()?;
+                    (page, page)
+                }
+            };
+            ranges.push(PageRange { from, to, parity });
+        }
+        Ok(PageSelection(ranges))
+    }
 }
 
 /// Base options shared between commands
 #[derive(Debug, Parser)]
 pub struct BaseOptions {
-    /// Input PDF file
-    #[arg(value_hint = clap::ValueHint::FilePath)]
-    pub input: PathBuf,
+    /// Input PDF files, concatenated in order
+    #[arg(
+        value_hint = clap::ValueHint::FilePath,
+        required_unless_present = "input_list"
+    )]
+    pub input: Vec<PathBuf>,
+
+    /// Text file listing input PDF files, one per line, after any positional inputs.
+    /// Relative paths are relative to the list file, blank lines and `#` comments are ignored
+    #[arg(long, value_hint = clap::ValueHint::FilePath)]
+    pub input_list: Option<PathBuf>,
+
+    /// Page each merged document starts on, blank pages are inserted before it as needed
+    #[arg(long, value_enum, default_value = "any")]
+    pub document_start: DocumentStart,
 
     /// Output PDF file, default is same folder
     #[arg(short, long, value_hint = clap::ValueHint::FilePath)]
@@ -74,6 +526,42 @@ pub struct BaseOptions {
     /// Output to temporary folder and print the path
     #[arg(short, long, default_value = "false")]
     pub temp: bool,
+
+    /// Pages to use, in order (e.g. 1-10,15,20-end,r3-r1,even,odd), default is every page
+    #[arg(long)]
+    pub pages: Option<PageSelection>,
+
+    /// Produce byte-identical output for the same input and options, dated from
+    /// SOURCE_DATE_EPOCH (the Unix epoch when unset)
+    #[arg(long, default_value = "false")]
+    pub reproducible: bool,
+}
+
+impl BaseOptions {
+    /// The input used to name the output: the first input file, else the list file
+    pub fn primary_input(&self) -> PathBuf {
+        self.input
+            .first()
+            .or(self.input_list.as_ref())
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Options of the subcommands that refuse an input already imposed
+#[derive(Debug, Parser)]
+pub struct ImposeOptions {
+    /// Impose the input even when it is already imposed
+    #[arg(long, default_value = "false")]
+    pub force: bool,
+}
+
+/// Options of the subcommands that place pages on sheets
+#[derive(Debug, Parser)]
+pub struct ComposeOptions {
+    /// Annotations (links, comments) of pages placed on sheets
+    #[arg(long, value_enum, default_value = "keep")]
+    pub annotations: AnnotationMode,
 }
 
 /// Booklet imposition options
@@ -82,9 +570,84 @@ pub struct BookletOptions {
     #[command(flatten)]
     pub base: BaseOptions,
 
+    #[command(flatten)]
+    pub impose: ImposeOptions,
+
+    #[command(flatten)]
+    pub compose: ComposeOptions,
+
     /// Layout type
     #[arg(long, value_enum, default_value = "four-up")]
     pub layout: LayoutType,
+
+    /// Binding edge, default follows the PDF /ViewerPreferences /Direction (ltr if absent)
+    #[arg(long, value_enum)]
+    pub binding: Option<Binding>,
+
+    /// Binding method
+    #[arg(long, value_enum, default_value = "saddle")]
+    pub binding_method: BindingMethod,
+
+    /// Warn when a saddle-stitched booklet needs more sheets than this
+    #[arg(long, default_value = "12")]
+    pub saddle_sheet_limit: u32,
+
+    /// Split a saddle-stitched booklet into signatures of this many sheets, or `auto` to
+    /// balance signatures of at most --saddle-sheet-limit sheets
+    #[arg(long)]
+    pub signature_size: Option<SignatureSize>,
+
+    /// Compose printed sheets with printer's marks: crop, fold and collation marks, and a slug
+    #[arg(long, default_value = "false")]
+    pub marks: bool,
+
+    /// Where blank pages go: end, before-last, after-first, or a list of pages to insert
+    /// a blank after (e.g. 1,12)
+    #[arg(long, default_value = "end")]
+    pub blank_position: BlankPosition,
+
+    /// Insert blank pages so every chapter of the document outline starts on a right-hand page
+    #[arg(long, default_value = "false")]
+    pub recto_chapters: bool,
+
+    /// First pages of the chapters to start on right-hand pages, instead of the outline
+    #[arg(long, value_delimiter = ',')]
+    pub chapter_pages: Option<Vec<u32>>,
+
+    /// Cover PDF of 4 pages (outside front, inside front, inside back, outside back),
+    /// imposed on its own sheet in a separate output file
+    #[arg(long, value_hint = clap::ValueHint::FilePath, conflicts_with = "cover_pages")]
+    pub cover: Option<PathBuf>,
+
+    /// Take the 4 cover pages out of the document, negative numbers count from the end
+    #[arg(long, value_delimiter = ',', allow_negative_numbers = true)]
+    pub cover_pages: Option<Vec<i32>>,
+}
+
+/// Unbooklet options
+#[derive(Debug, Parser)]
+pub struct UnbookletOptions {
+    /// Input PDF file of imposed sheet sides, fronts and backs alternating
+    #[command(flatten)]
+    pub base: BaseOptions,
+
+    #[command(flatten)]
+    pub compose: ComposeOptions,
+
+    /// Layout the booklet was imposed with, default is the layout recorded by bookify-rs
+    /// (four-up if absent)
+    #[arg(long, value_enum)]
+    pub layout: Option<LayoutType>,
+
+    /// Binding edge the booklet was imposed with, default is the binding recorded by
+    /// bookify-rs, else the PDF /ViewerPreferences /Direction (ltr if absent)
+    #[arg(long, value_enum)]
+    pub binding: Option<Binding>,
+
+    /// Border around the pages to cut away in millimetres, such as a scanner border,
+    /// default is the slug of sheets printed by bookify-rs (0 for other sheets)
+    #[arg(long)]
+    pub margin: Option<f32>,
 }
 
 /// Double-sided printing options
@@ -93,6 +656,9 @@ pub struct DoubleSidedOptions {
     #[command(flatten)]
     pub base: BaseOptions,
 
+    #[command(flatten)]
+    pub impose: ImposeOptions,
+
     /// Flip type, default is flip on both odd and even pages
     #[arg(long, value_enum, default_value = "rr")]
     pub flip_type: FlipType,
@@ -102,6 +668,288 @@ pub struct DoubleSidedOptions {
     pub odd_even: OddEven,
 }
 
+/// Interleave options
+#[derive(Debug, Parser)]
+pub struct InterleaveOptions {
+    /// Input PDF file holding the scanned fronts (odd pages)
+    #[command(flatten)]
+    pub base: BaseOptions,
+
+    /// PDF file holding the scanned backs (even pages)
+    #[arg(long, value_hint = clap::ValueHint::FilePath)]
+    pub backs: PathBuf,
+
+    /// Order of the scanned stacks, first letter fronts and second letter backs, r is reversed
+    #[arg(long, value_enum, default_value = "nr")]
+    pub flip_type: FlipType,
+}
+
+/// Step-and-repeat grid options
+#[derive(Debug, Parser)]
+pub struct GridOptions {
+    #[command(flatten)]
+    pub base: BaseOptions,
+
+    #[command(flatten)]
+    pub impose: ImposeOptions,
+
+    #[command(flatten)]
+    pub compose: ComposeOptions,
+
+    /// Number of columns of the grid
+    #[arg(long, default_value = "2", value_parser = clap::value_parser!(u32).range(1..))]
+    pub columns: u32,
+
+    /// Number of rows of the grid
+    #[arg(long, default_value = "5", value_parser = clap::value_parser!(u32).range(1..))]
+    pub rows: u32,
+
+    /// Fill mode, repeat each page or place consecutive pages
+    #[arg(long, value_enum, default_value = "repeat")]
+    pub mode: GridMode,
+
+    /// Duplex printing, backs are mirrored so they register with their fronts
+    #[arg(long, value_enum, default_value = "none")]
+    pub duplex: Duplex,
+
+    /// Space between grid cells in millimetres
+    #[arg(long, default_value = "0", value_parser = non_negative_length)]
+    pub gutter: f32,
+
+    /// Minimum space between the grid and the sheet edges in millimetres
+    #[arg(long, default_value = "10", value_parser = non_negative_length)]
+    pub margin: f32,
+
+    /// Sheet size (a4, letter, a3-landscape, 210x297mm...), default fits the grid
+    #[arg(long)]
+    pub sheet: Option<PaperSize>,
+
+    /// Draw cut marks around every cell
+    #[arg(long, default_value = "false")]
+    pub cut_marks: bool,
+}
+
+/// Flashcard options
+#[derive(Debug, Parser)]
+pub struct FlashcardOptions {
+    /// Input PDF file holding the questions (fronts)
+    #[command(flatten)]
+    pub base: BaseOptions,
+
+    #[command(flatten)]
+    pub impose: ImposeOptions,
+
+    #[command(flatten)]
+    pub compose: ComposeOptions,
+
+    /// PDF file holding the answers (backs), answer i belongs to question i
+    #[arg(long, value_hint = clap::ValueHint::FilePath)]
+    pub answers: PathBuf,
+
+    /// Number of columns of the grid
+    #[arg(long, default_value = "2", value_parser = clap::value_parser!(u32).range(1..))]
+    pub columns: u32,
+
+    /// Number of rows of the grid
+    #[arg(long, default_value = "4", value_parser = clap::value_parser!(u32).range(1..))]
+    pub rows: u32,
+
+    /// Edge the sheet is flipped on when printing the answers
+    #[arg(long, value_enum, default_value = "long-edge")]
+    pub duplex: Duplex,
+
+    /// Space between cards in millimetres
+    #[arg(long, default_value = "0", value_parser = non_negative_length)]
+    pub gutter: f32,
+
+    /// Minimum space between the grid and the sheet edges in millimetres
+    #[arg(long, default_value = "10", value_parser = non_negative_length)]
+    pub margin: f32,
+
+    /// Sheet size (a4, letter, a3-landscape, 210x297mm...), default fits the grid
+    #[arg(long)]
+    pub sheet: Option<PaperSize>,
+
+    /// Draw cut marks around every card
+    #[arg(long, default_value = "false")]
+    pub cut_marks: bool,
+}
+
+/// Poster options
+#[derive(Debug, Parser)]
+pub struct PosterOptions {
+    #[command(flatten)]
+    pub base: BaseOptions,
+
+    #[command(flatten)]
+    pub impose: ImposeOptions,
+
+    #[command(flatten)]
+    pub compose: ComposeOptions,
+
+    /// Size of the sheets the poster is tiled on (a4, letter, 210x297mm...)
+    #[arg(long, default_value = "a4")]
+    pub sheet: PaperSize,
+
+    /// Enlargement factor of the poster page
+    #[arg(long, default_value = "1.0")]
+    pub scale: f32,
+
+    /// Width of the strip shared by neighbouring tiles in millimetres
+    #[arg(long, default_value = "10", value_parser = non_negative_length)]
+    pub overlap: f32,
+
+    /// Space between the printed area and the sheet edges in millimetres
+    #[arg(long, default_value = "10", value_parser = non_negative_length)]
+    pub margin: f32,
+
+    /// Do not add the overview page before the tiles
+    #[arg(long, default_value = "false")]
+    pub no_overview: bool,
+}
+
+/// Brochure options
+#[derive(Debug, Parser)]
+pub struct BrochureOptions {
+    #[command(flatten)]
+    pub base: BaseOptions,
+
+    #[command(flatten)]
+    pub impose: ImposeOptions,
+
+    #[command(flatten)]
+    pub compose: ComposeOptions,
+
+    /// Brochure fold
+    #[arg(long, value_enum, default_value = "tri-fold")]
+    pub fold: FoldType,
+
+    /// Sheet size (letter-landscape, a4-landscape, 279x216mm...)
+    #[arg(long, default_value = "letter-landscape")]
+    pub sheet: PaperSize,
+
+    /// Width taken off the panels folding inwards, in millimetres
+    #[arg(long, default_value = "2")]
+    pub tuck: f32,
+
+    /// Draw fold marks at the sheet edges
+    #[arg(long, default_value = "false")]
+    pub fold_marks: bool,
+}
+
+/// Handout options
+#[derive(Debug, Parser)]
+pub struct HandoutOptions {
+    #[command(flatten)]
+    pub base: BaseOptions,
+
+    #[command(flatten)]
+    pub impose: ImposeOptions,
+
+    #[command(flatten)]
+    pub compose: ComposeOptions,
+
+    /// Slides per page: 2, 3, 4, 6 or 9
+    #[arg(long, default_value = "3")]
+    pub slides: u32,
+
+    /// Draw ruled lines for notes beside each slide
+    #[arg(long, default_value = "false")]
+    pub notes: bool,
+
+    /// Page header text, default is the input file name
+    #[arg(long)]
+    pub header: Option<String>,
+
+    /// Sheet size (a4, letter, 210x297mm...)
+    #[arg(long, default_value = "a4")]
+    pub sheet: PaperSize,
+
+    /// Space between the slides and the sheet edges in millimetres
+    #[arg(long, default_value = "15", value_parser = non_negative_length)]
+    pub margin: f32,
+}
+
+/// Wrap-around cover options
+#[derive(Debug, Parser)]
+pub struct CoverOptions {
+    /// Input PDF file of the book block, giving the trim size and page count
+    #[command(flatten)]
+    pub base: BaseOptions,
+
+    #[command(flatten)]
+    pub impose: ImposeOptions,
+
+    #[command(flatten)]
+    pub compose: ComposeOptions,
+
+    /// Cover art PDF: front cover, back cover and optionally the spine
+    #[arg(long, value_hint = clap::ValueHint::FilePath)]
+    pub art: PathBuf,
+
+    /// Paper thickness in pages per inch
+    #[arg(long, required_unless_present = "caliper", value_parser = positive_number)]
+    pub ppi: Option<f32>,
+
+    /// Paper thickness in millimetres per sheet
+    #[arg(long, conflicts_with = "ppi", value_parser = positive_number)]
+    pub caliper: Option<f32>,
+
+    /// Title printed along the spine, Western European characters only
+    #[arg(long)]
+    pub spine_text: Option<String>,
+
+    /// Binding edge, default follows the PDF /ViewerPreferences /Direction (ltr if absent)
+    #[arg(long, value_enum)]
+    pub binding: Option<Binding>,
+
+    /// Surround the spread with crop marks and spine fold marks
+    #[arg(long, default_value = "false")]
+    pub marks: bool,
+}
+
+/// Page extraction options
+#[derive(Debug, Parser)]
+pub struct ExtractOptions {
+    /// Input PDF file, --pages selects the pages to extract
+    #[command(flatten)]
+    pub base: BaseOptions,
+}
+
+/// Document splitting options
+#[derive(Debug, Parser)]
+pub struct SplitOptions {
+    #[command(flatten)]
+    pub base: BaseOptions,
+
+    /// Number of pages in each part, the last part may be shorter
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub every: u32,
+}
+
+/// Page rotation options
+#[derive(Debug, Parser)]
+pub struct RotateOptions {
+    /// Input PDF file, --pages selects the pages to rotate and every page is kept
+    #[command(flatten)]
+    pub base: BaseOptions,
+
+    /// Clockwise rotation in degrees, a multiple of 90 (negative turns counterclockwise)
+    #[arg(long, allow_negative_numbers = true)]
+    pub angle: i64,
+}
+
+/// Blank page insertion options
+#[derive(Debug, Parser)]
+pub struct InsertBlankOptions {
+    #[command(flatten)]
+    pub base: BaseOptions,
+
+    /// Pages to insert a blank page after, 0 inserts before the first page (e.g. 0,12,12)
+    #[arg(long, value_delimiter = ',', required = true)]
+    pub after: Vec<u32>,
+}
+
 /// Command line parameters
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -117,7 +965,165 @@ pub enum Commands {
     #[command(name = "booklet")]
     Booklet(BookletOptions),
 
+    /// Unbooklet: Cut imposed booklet sheets, such as a scanned zine, back into pages in reading order
+    #[command(name = "unbooklet")]
+    Unbooklet(UnbookletOptions),
+
     /// Double-sided printing: Convert PDF to format suitable for double-sided printing
     #[command(name = "double-sided")]
     DoubleSided(DoubleSidedOptions),
+
+    /// Interleave: Merge separately scanned fronts and backs back into reading order
+    #[command(name = "interleave")]
+    Interleave(InterleaveOptions),
+
+    /// Step-and-repeat: Place pages in a grid on each sheet for cards, labels and tickets
+    #[command(name = "grid")]
+    Grid(GridOptions),
+
+    /// Flashcards: Place questions and answers from two PDFs on the fronts and backs of a grid
+    #[command(name = "flashcards")]
+    Flashcards(FlashcardOptions),
+
+    /// Poster: Enlarge pages and tile them across several sheets to be glued together
+    #[command(name = "poster")]
+    Poster(PosterOptions),
+
+    /// Brochure: Place panels of tri-fold, Z-fold and gate-fold brochures on both sides of a sheet
+    #[command(name = "brochure")]
+    Brochure(BrochureOptions),
+
+    /// Handout: Print presentation slides several per page, with headers, numbers and notes
+    #[command(name = "handout")]
+    Handout(HandoutOptions),
+
+    /// Cover: Build the wrap-around cover of a perfect-bound book with its computed spine
+    #[command(name = "cover")]
+    Cover(CoverOptions),
+
+    /// Extract: Save the pages selected with --pages as a new PDF
+    #[command(name = "extract")]
+    Extract(ExtractOptions),
+
+    /// Split: Save every N pages as a separate PDF
+    #[command(name = "split")]
+    Split(SplitOptions),
+
+    /// Rotate: Turn the pages selected with --pages, keeping the other pages as they are
+    #[command(name = "rotate")]
+    Rotate(RotateOptions),
+
+    /// Insert blank: Add blank pages after the given pages
+    #[command(name = "insert-blank")]
+    InsertBlank(InsertBlankOptions),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_selection_parity_suffix() {
+        let selection: PageSelection = "1-6odd, r2-1even".parse().unwrap();
+        assert_eq!(selection.resolve(6).unwrap(), vec![1, 3, 5, 4, 2]);
+    }
+
+    #[test]
+    fn test_page_selection_errors() {
+        assert!("1-".parse::<PageSelection>().is_err());
+        assert!("r0".parse::<PageSelection>().is_err());
+        assert!("x".parse::<PageSelection>().is_err());
+        let selection: PageSelection = "2-12".parse().unwrap();
+        assert!(selection.resolve(10).is_err());
+        let selection: PageSelection = "r11".parse().unwrap();
+        assert!(selection.resolve(10).is_err());
+    }
+
+    #[test]
+    fn test_signature_size_parse() {
+        assert_eq!("4".parse(), Ok(SignatureSize::Sheets(4)));
+        assert_eq!("Auto".parse(), Ok(SignatureSize::Auto));
+        assert!("0".parse::<SignatureSize>().is_err());
+    }
+
+    #[test]
+    fn test_non_negative_length() {
+        assert_eq!(non_negative_length("2.5"), Ok(2.5));
+        assert_eq!(non_negative_length("0"), Ok(0.0));
+        assert!(non_negative_length("-1").is_err());
+        assert!(non_negative_length("wide").is_err());
+    }
+
+    #[test]
+    fn test_cover_paper_thickness_positive() {
+        assert_eq!(positive_number("444"), Ok(444.0));
+        assert!(positive_number("0").is_err());
+        assert!(positive_number("inf").is_err());
+        let parse = |option: &str| {
+            Cli::try_parse_from([
+                "bookify-rs",
+                "cover",
+                "book.pdf",
+                "--art",
+                "art.pdf",
+                option,
+            ])
+        };
+        assert!(parse("--ppi=444").is_ok());
+        assert!(parse("--ppi=0").is_err());
+        assert!(parse("--caliper=0.1").is_ok());
+        assert!(parse("--caliper=-1").is_err());
+    }
+
+    #[test]
+    fn test_poster_overlap_non_negative() {
+        let parse = |option: &str| Cli::try_parse_from(["bookify-rs", "poster", "map.pdf", option]);
+        assert!(parse("--overlap=0").is_ok());
+        assert!(parse("--overlap=-5").is_err());
+    }
+
+    #[test]
+    fn test_handout_margin_non_negative() {
+        let parse =
+            |option: &str| Cli::try_parse_from(["bookify-rs", "handout", "deck.pdf", option]);
+        assert!(parse("--margin=0").is_ok());
+        assert!(parse("--margin=-50").is_err());
+    }
+
+    #[test]
+    fn test_blank_position_parse() {
+        assert_eq!("before-last".parse(), Ok(BlankPosition::BeforeLast));
+        assert_eq!("0, 12".parse(), Ok(BlankPosition::After(vec![0, 12])));
+        assert!("middle".parse::<BlankPosition>().is_err());
+    }
+
+    #[test]
+    fn test_paper_size_names() {
+        let a4: PaperSize = "A4".parse().unwrap();
+        assert!((a4.width - 595.28).abs() < 0.01);
+        assert!((a4.height - 841.89).abs() < 0.01);
+
+        let letter: PaperSize = "letter-landscape".parse().unwrap();
+        assert_eq!((letter.width, letter.height), (792.0, 612.0));
+    }
+
+    #[test]
+    fn test_paper_size_dimensions() {
+        let size: PaperSize = "8.5x11in".parse().unwrap();
+        assert_eq!((size.width, size.height), (612.0, 792.0));
+
+        let size: PaperSize = "100x50pt".parse().unwrap();
+        assert_eq!((size.width, size.height), (100.0, 50.0));
+
+        let size: PaperSize = "25.4x50.8".parse().unwrap();
+        assert!((size.width - 72.0).abs() < 0.01);
+        assert!((size.height - 144.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_paper_size_invalid() {
+        assert!("b4".parse::<PaperSize>().is_err());
+        assert!("0x10mm".parse::<PaperSize>().is_err());
+        assert!("10mm".parse::<PaperSize>().is_err());
+    }
 }
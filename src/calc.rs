@@ -1,4 +1,16 @@
-use crate::args::{FlipType, LayoutType, OddEven};
+use crate::args::{
+    Binding, BindingMethod, BlankPosition, DocumentStart, FlipType, FoldType, GridMode, LayoutType,
+    OddEven,
+};
+
+/// Axis a duplex sheet is turned around to print its back
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FlipAxis {
+    /// Turned left to right: back columns are mirrored
+    Vertical,
+    /// Turned top to bottom: back rows are mirrored
+    Horizontal,
+}
 
 /// Generates a booklet imposition sequence based on page count and layout type.
 ///
@@ -6,6 +18,9 @@ use crate::args::{FlipType, LayoutType, OddEven};
 /// If the input `n` is not a multiple, it will be rounded up to the nearest multiple,
 /// with blank pages (represented by 0) added as needed.
 ///
+/// `LayoutType::MiniZine` is single-sided: each sheet is a separate 8-page zine whose
+/// top row (the first 4 slots) is printed upside down.
+///
 /// # Parameters
 /// * `n` - Total number of pages in the booklet
 /// * `layout` - Layout type defining pages per sheet
@@ -23,6 +38,9 @@ use crate::args::{FlipType, LayoutType, OddEven};
 ///
 /// let imposition_2up = generate_booklet_imposition(8, LayoutType::TwoUp);
 /// assert_eq!(imposition_2up, vec![8, 1, 2, 7, 6, 3, 4, 5]);
+///
+/// let imposition_zine = generate_booklet_imposition(8, LayoutType::MiniZine);
+/// assert_eq!(imposition_zine, vec![5, 4, 3, 2, 6, 7, 8, 1]);
 /// ```
 pub fn generate_booklet_imposition(n: u32, layout: LayoutType) -> Vec<u32> {
     // 1. Handle special case: page count is 0
@@ -34,6 +52,7 @@ pub fn generate_booklet_imposition(n: u32, layout: LayoutType) -> Vec<u32> {
     let pages_per_physical_sheet: u32 = match layout {
         LayoutType::TwoUp => 4,
         LayoutType::FourUp => 8,
+        LayoutType::MiniZine => 8,
     };
 
     // 3. Determine total pages needed for booklet imposition, must be multiple of pages_per_physical_sheet
@@ -83,6 +102,17 @@ pub fn generate_booklet_imposition(n: u32, layout: LayoutType) -> Vec<u32> {
                 ];
                 imposition_list.extend_from_slice(&side_b_pages);
             }
+            LayoutType::MiniZine => {
+                // 8 pages on a single side, each sheet is a separate zine
+                let base = 8 * k;
+                // TOP ROW (upside down, Left to Right)
+                let top_row = [base + 5, base + 4, base + 3, base + 2];
+                imposition_list.extend_from_slice(&top_row);
+
+                // BOTTOM ROW (Left to Right): back cover next to front cover
+                let bottom_row = [base + 6, base + 7, base + 8, base + 1];
+                imposition_list.extend_from_slice(&bottom_row);
+            }
         }
     }
 
@@ -95,6 +125,708 @@ pub fn generate_booklet_imposition(n: u32, layout: LayoutType) -> Vec<u32> {
     final_imposition_list
 }
 
+/// Pads a document with blank pages at the requested positions.
+///
+/// # Parameters
+/// * `n` - Total number of pages in the document
+/// * `padded_pages` - Page count after padding, at least `n`
+/// * `position` - Where the blank pages go
+///
+/// # Returns
+/// `Vec<u32>` - Source page of each padded page, 0 represents a blank page.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::BlankPosition, calc::pad_pages};
+///
+/// assert_eq!(pad_pages(5, 8, &BlankPosition::BeforeLast), vec![1, 2, 3, 4, 0, 0, 0, 5]);
+/// assert_eq!(pad_pages(5, 8, &BlankPosition::After(vec![1, 3])), vec![1, 0, 2, 3, 0, 4, 5, 0]);
+/// ```
+pub fn pad_pages(n: u32, padded_pages: u32, position: &BlankPosition) -> Vec<u32> {
+    let blanks = padded_pages.saturating_sub(n) as usize;
+    let after: Vec<u32> = match position {
+        BlankPosition::End => vec![n; blanks],
+        BlankPosition::BeforeLast => vec![n.saturating_sub(1); blanks],
+        BlankPosition::AfterFirst => vec![n.min(1); blanks],
+        BlankPosition::After(pages) => pages
+            .iter()
+            .map(|&page| page.min(n))
+            .chain(std::iter::repeat(n))
+            .take(blanks)
+            .collect(),
+    };
+    generate_blank_insertion(n, &after)
+}
+
+/// Inserts a blank page after each of the given pages.
+///
+/// # Parameters
+/// * `n` - Total number of pages in the document
+/// * `after` - Pages to insert a blank after, 0 inserts before the first page.
+///   A page listed several times gets several blanks.
+///
+/// # Returns
+/// `Vec<u32>` - Source page of each resulting page, 0 represents a blank page.
+///
+/// # Example
+/// ```
+/// use bookify_rs::calc::generate_blank_insertion;
+///
+/// assert_eq!(generate_blank_insertion(3, &[0, 2, 2]), vec![0, 1, 2, 0, 0, 3]);
+/// ```
+pub fn generate_blank_insertion(n: u32, after: &[u32]) -> Vec<u32> {
+    let mut pages = Vec::with_capacity(n as usize + after.len());
+    for page in 0..=n {
+        if page > 0 {
+            pages.push(page);
+        }
+        pages.extend(after.iter().filter(|&&after| after == page).map(|_| 0));
+    }
+    pages
+}
+
+/// Inserts blank pages so that every chapter starts on a recto (right-hand) page.
+///
+/// Recto pages are the odd pages of the padded document, whatever the reading direction.
+/// A blank page is inserted before each chapter that would otherwise start on a verso.
+///
+/// # Parameters
+/// * `n` - Total number of pages in the document
+/// * `chapter_starts` - First page of each chapter, in any order. Pages outside the
+///   document and the first page are ignored.
+///
+/// # Returns
+/// `Vec<u32>` - Source page of each padded page, 0 represents a blank page.
+///
+/// # Example
+/// ```
+/// use bookify_rs::calc::generate_recto_padding;
+///
+/// // Chapters on pages 4 and 6: page 4 moves to 5, page 6 is then already on 7
+/// assert_eq!(generate_recto_padding(7, &[4, 6]), vec![1, 2, 3, 0, 4, 5, 6, 7]);
+/// ```
+pub fn generate_recto_padding(n: u32, chapter_starts: &[u32]) -> Vec<u32> {
+    let mut padded = Vec::with_capacity(n as usize);
+    for page in 1..=n {
+        // Next position is a verso when it is even
+        if page > 1 && chapter_starts.contains(&page) && padded.len() % 2 == 1 {
+            padded.push(0);
+        }
+        padded.push(page);
+    }
+    padded
+}
+
+/// Number of blank pages to insert before a document appended after `n` pages,
+/// so that it starts on the requested page parity.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::DocumentStart, calc::document_start_blanks};
+///
+/// // After 3 pages the next page is 4, an even page
+/// assert_eq!(document_start_blanks(3, DocumentStart::Odd), 1);
+/// assert_eq!(document_start_blanks(3, DocumentStart::Even), 0);
+/// ```
+pub fn document_start_blanks(n: u32, start: DocumentStart) -> u32 {
+    let next_is_odd = n.is_multiple_of(2);
+    match start {
+        DocumentStart::Any => 0,
+        DocumentStart::Odd => u32::from(!next_is_odd),
+        DocumentStart::Even => u32::from(next_is_odd),
+    }
+}
+
+/// Splits a saddle-stitched book into signatures of a fixed number of sheets.
+///
+/// # Parameters
+/// * `n` - Total number of pages in the book
+/// * `layout` - Layout type defining pages per sheet
+/// * `sheets_per_signature` - Maximum number of nested sheets in one signature
+///
+/// # Returns
+/// `Vec<u32>` - Number of sheets of each signature, the last one may be smaller.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::LayoutType, calc::split_signatures};
+///
+/// assert_eq!(split_signatures(40, LayoutType::TwoUp, 4), vec![4, 4, 2]);
+/// ```
+pub fn split_signatures(n: u32, layout: LayoutType, sheets_per_signature: u32) -> Vec<u32> {
+    let sheets_per_signature = sheets_per_signature.max(1);
+    let mut remaining = sheet_count(n, layout);
+    let mut signatures = Vec::new();
+    while remaining > 0 {
+        let sheets = remaining.min(sheets_per_signature);
+        signatures.push(sheets);
+        remaining -= sheets;
+    }
+    signatures
+}
+
+/// Splits a saddle-stitched book into as few signatures as possible, sized evenly.
+///
+/// Every sheet holds the same number of pages, so the blank pages of a book only depend
+/// on its sheet count: any breakdown into whole sheets has the fewest blanks. Among those,
+/// this one has the fewest signatures and avoids a thin last signature, for example
+/// 4+4+3 sheets instead of 4+4+2+1. Larger signatures come first.
+///
+/// # Parameters
+/// * `n` - Total number of pages in the book
+/// * `layout` - Layout type defining pages per sheet
+/// * `max_sheets` - Maximum number of nested sheets in one signature
+///
+/// # Returns
+/// `Vec<u32>` - Number of sheets of each signature.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::LayoutType, calc::balance_signatures};
+///
+/// // 9 sheets: 3+3+3 rather than 4+4+1
+/// assert_eq!(balance_signatures(36, LayoutType::TwoUp, 4), vec![3, 3, 3]);
+/// assert_eq!(balance_signatures(88, LayoutType::FourUp, 4), vec![4, 4, 3]);
+/// ```
+pub fn balance_signatures(n: u32, layout: LayoutType, max_sheets: u32) -> Vec<u32> {
+    let total_sheets = sheet_count(n, layout);
+    let count = total_sheets.div_ceil(max_sheets.max(1));
+    if count == 0 {
+        return Vec::new();
+    }
+    let (base, extra) = (total_sheets / count, total_sheets % count);
+    (0..count)
+        .map(|index| if index < extra { base + 1 } else { base })
+        .collect()
+}
+
+/// Generates a saddle-stitched imposition sequence for a book made of several signatures.
+///
+/// Each signature is imposed like `generate_booklet_imposition` on its own run of
+/// consecutive pages, and signatures are gathered one after the other. Pages beyond `n`
+/// become blank pages (0).
+///
+/// # Parameters
+/// * `n` - Total number of pages in the book
+/// * `layout` - Layout type defining pages per sheet
+/// * `signatures` - Number of sheets of each signature, see `split_signatures`
+///
+/// # Returns
+/// `Vec<u32>` - Page sequence ordered for printing (left-to-right, top-to-bottom, front then back).
+///             0 represents a blank page.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::LayoutType, calc::generate_signature_imposition};
+///
+/// let imposition = generate_signature_imposition(7, LayoutType::TwoUp, &[1, 1]);
+/// assert_eq!(imposition, vec![4, 1, 2, 3, 0, 5, 6, 7]);
+/// ```
+pub fn generate_signature_imposition(n: u32, layout: LayoutType, signatures: &[u32]) -> Vec<u32> {
+    let mut imposition_list: Vec<u32> = Vec::new();
+    let mut first_page = 0;
+    for &sheets in signatures {
+        let signature_pages = sheets * layout.pages_per_sheet();
+        imposition_list.extend(
+            generate_booklet_imposition(signature_pages, layout)
+                .into_iter()
+                .map(|p| {
+                    if first_page + p > n {
+                        0
+                    } else {
+                        first_page + p
+                    }
+                }),
+        );
+        first_page += signature_pages;
+    }
+    imposition_list
+}
+
+/// Generates a perfect-binding imposition sequence based on page count and layout type.
+///
+/// Every folio (a 2-up half sheet folded once) holds 4 consecutive pages, and folios are
+/// stacked in order instead of being nested. `LayoutType::FourUp` prints two folios per
+/// sheet, one per row. The sequence is padded with blank pages (0) like
+/// `generate_booklet_imposition`.
+///
+/// # Parameters
+/// * `n` - Total number of pages in the book
+/// * `layout` - Layout type defining pages per sheet, `TwoUp` or `FourUp`
+///
+/// # Returns
+/// `Vec<u32>` - Page sequence ordered for printing (left-to-right, top-to-bottom, front then back).
+///             0 represents a blank page.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::LayoutType, calc::generate_perfect_binding_imposition};
+///
+/// let imposition = generate_perfect_binding_imposition(8, LayoutType::TwoUp);
+/// assert_eq!(imposition, vec![4, 1, 2, 3, 8, 5, 6, 7]);
+/// ```
+pub fn generate_perfect_binding_imposition(n: u32, layout: LayoutType) -> Vec<u32> {
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let pages_per_physical_sheet = layout.pages_per_sheet();
+    let total_pages = n.div_ceil(pages_per_physical_sheet) * pages_per_physical_sheet;
+
+    // Each folio is one row of the sheet, both sides
+    let num_folios = total_pages / 4;
+    let folios_per_sheet = (pages_per_physical_sheet / 4) as usize;
+    let mut imposition_list: Vec<u32> = Vec::with_capacity(total_pages as usize);
+
+    let folios: Vec<u32> = (0..num_folios).collect();
+    for sheet_folios in folios.chunks(folios_per_sheet) {
+        // SIDE A: outer side of each folio (back, front)
+        for &f in sheet_folios {
+            imposition_list.extend_from_slice(&[4 * f + 4, 4 * f + 1]);
+        }
+        // SIDE B: inner side of each folio
+        for &f in sheet_folios {
+            imposition_list.extend_from_slice(&[4 * f + 2, 4 * f + 3]);
+        }
+    }
+
+    imposition_list
+        .into_iter()
+        .map(|p| if p > n { 0 } else { p })
+        .collect()
+}
+
+/// Generates a cut-and-stack imposition sequence based on page count and layout type.
+///
+/// Sheets are printed, cut into single leaves, and each slot position forms a stack.
+/// Stacks are then put on top of each other in slot order (left-to-right, top-to-bottom),
+/// so the first stack holds the first leaves of the book. Back sides mirror the front
+/// slots of each row, matching long-edge duplex printing.
+///
+/// # Parameters
+/// * `n` - Total number of pages in the book
+/// * `layout` - Layout type defining pages per sheet, `TwoUp` or `FourUp`
+///
+/// # Returns
+/// `Vec<u32>` - Page sequence ordered for printing (left-to-right, top-to-bottom, front then back).
+///             0 represents a blank page.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::LayoutType, calc::generate_cut_stack_imposition};
+///
+/// let imposition = generate_cut_stack_imposition(8, LayoutType::TwoUp);
+/// assert_eq!(imposition, vec![1, 5, 6, 2, 3, 7, 8, 4]);
+/// ```
+pub fn generate_cut_stack_imposition(n: u32, layout: LayoutType) -> Vec<u32> {
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let pages_per_physical_sheet = layout.pages_per_sheet();
+    let num_physical_sheets = n.div_ceil(pages_per_physical_sheet);
+    let slots_per_side = layout.pages_per_side() as u32;
+
+    // Leaf held by a slot of a sheet: stacks are read one after the other
+    let leaf = |slot: u32, sheet: u32| slot * num_physical_sheets + sheet;
+
+    let mut imposition_list: Vec<u32> = Vec::new();
+    for k in 0..num_physical_sheets {
+        // SIDE A: front of each leaf
+        for slot in 0..slots_per_side {
+            imposition_list.push(2 * leaf(slot, k) + 1);
+        }
+        // SIDE B: back of each leaf, mirrored within each row
+        for slot in 0..slots_per_side {
+            imposition_list.push(2 * leaf(slot ^ 1, k) + 2);
+        }
+    }
+
+    imposition_list
+        .into_iter()
+        .map(|p| if p > n { 0 } else { p })
+        .collect()
+}
+
+/// Generates the imposition sequence of a booklet bound with `method`.
+///
+/// Saddle-stitched booklets are imposed as the given signatures, perfect-bound and
+/// cut-and-stack booklets ignore them.
+///
+/// # Parameters
+/// * `n` - Total number of pages in the book, a multiple of the pages per sheet
+/// * `layout` - Layout type defining pages per sheet
+/// * `method` - Binding method
+/// * `signatures` - Number of sheets of each signature of a saddle-stitched booklet
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::{BindingMethod, LayoutType}, calc::generate_bound_imposition};
+///
+/// let saddle = generate_bound_imposition(8, LayoutType::TwoUp, BindingMethod::Saddle, &[2]);
+/// assert_eq!(saddle, vec![8, 1, 2, 7, 6, 3, 4, 5]);
+/// let perfect = generate_bound_imposition(8, LayoutType::TwoUp, BindingMethod::Perfect, &[2]);
+/// assert_eq!(perfect, vec![4, 1, 2, 3, 8, 5, 6, 7]);
+/// ```
+pub fn generate_bound_imposition(
+    n: u32,
+    layout: LayoutType,
+    method: BindingMethod,
+    signatures: &[u32],
+) -> Vec<u32> {
+    match method {
+        BindingMethod::Saddle => generate_signature_imposition(n, layout, signatures),
+        BindingMethod::Perfect => generate_perfect_binding_imposition(n, layout),
+        BindingMethod::CutStack => generate_cut_stack_imposition(n, layout),
+    }
+}
+
+/// Resolves a page index counted from either end of a document.
+///
+/// Positive indices count from the first page (1 is the first page), negative indices
+/// from the last one (-1 is the last page).
+///
+/// # Returns
+/// `Option<u32>` - 1-based page number, `None` when the index is 0 or outside the document
+///
+/// # Example
+/// ```
+/// use bookify_rs::calc::resolve_page_index;
+///
+/// assert_eq!(resolve_page_index(2, 10), Some(2));
+/// assert_eq!(resolve_page_index(-2, 10), Some(9));
+/// assert_eq!(resolve_page_index(-11, 10), None);
+/// ```
+pub fn resolve_page_index(index: i32, total_pages: u32) -> Option<u32> {
+    let page = if index < 0 {
+        total_pages as i64 + 1 + index as i64
+    } else {
+        index as i64
+    };
+    (1..=total_pages as i64)
+        .contains(&page)
+        .then_some(page as u32)
+}
+
+/// Returns the number of physical sheets needed to print `n` pages with the given layout.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::LayoutType, calc::sheet_count};
+///
+/// assert_eq!(sheet_count(13, LayoutType::TwoUp), 4);
+/// assert_eq!(sheet_count(16, LayoutType::FourUp), 2);
+/// ```
+pub fn sheet_count(n: u32, layout: LayoutType) -> u32 {
+    n.div_ceil(layout.pages_per_sheet())
+}
+
+/// Applies the binding edge to a booklet imposition sequence.
+///
+/// `generate_booklet_imposition` lays pages out for left-to-right binding, with page 1
+/// on the right of the outer sheet. Therefore:
+/// * right-to-left binding mirrors the slots of each row to move the spine to the right;
+/// * top binding stacks each spread vertically (transposing the 2x2 grid of `FourUp`),
+///   and turns every back side by 180° by reversing its slots. The pages on those slots
+///   must be rotated as well, see `is_rotated_slot`.
+///
+/// # Parameters
+/// * `imposition` - Page sequence returned by `generate_booklet_imposition`
+/// * `layout` - Layout type the sequence was generated for
+/// * `binding` - Binding edge of the booklet
+///
+/// # Returns
+/// `Vec<u32>` - Page sequence ordered for the requested binding. 0 represents a blank page.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{
+///     args::{Binding, LayoutType},
+///     calc::{apply_binding, generate_booklet_imposition},
+/// };
+///
+/// let imposition = generate_booklet_imposition(8, LayoutType::TwoUp);
+/// let rtl = apply_binding(imposition.clone(), LayoutType::TwoUp, Binding::Rtl);
+/// assert_eq!(rtl, vec![1, 8, 7, 2, 3, 6, 5, 4]);
+///
+/// let top = apply_binding(imposition, LayoutType::TwoUp, Binding::Top);
+/// assert_eq!(top, vec![8, 1, 7, 2, 6, 3, 5, 4]);
+/// ```
+pub fn apply_binding(mut imposition: Vec<u32>, layout: LayoutType, binding: Binding) -> Vec<u32> {
+    match binding {
+        Binding::Ltr => {}
+        Binding::Rtl => {
+            // Mirror each row so the spine moves from the left to the right edge
+            for row in imposition.chunks_exact_mut(layout.pages_per_row()) {
+                row.reverse();
+            }
+        }
+        Binding::Top => {
+            let pages_per_side = layout.pages_per_side();
+            for (side_index, side) in imposition.chunks_exact_mut(pages_per_side).enumerate() {
+                // Stack spreads vertically: rows of the 2x2 grid become columns
+                if layout == LayoutType::FourUp {
+                    side.swap(1, 2);
+                }
+                // Back side is turned upside down
+                if side_index % 2 == 1 {
+                    side.reverse();
+                }
+            }
+        }
+    }
+    imposition
+}
+
+/// Recovers the reading order of a booklet from its imposed sheet sides.
+///
+/// This is the inverse of [`generate_bound_imposition`] followed by [`apply_binding`]:
+/// the imposed sequence lists the slots of every side, left-to-right, top-to-bottom,
+/// front then back.
+///
+/// # Parameters
+/// * `sides` - Number of imposed sheet sides, an even number
+/// * `layout` - Layout type the booklet was imposed with
+/// * `binding` - Binding edge the booklet was imposed with
+/// * `method` - Binding method the booklet was imposed with
+/// * `signatures` - Number of sheets of each signature of a saddle-stitched booklet, a single
+///   signature of all sheets when empty
+///
+/// # Returns
+/// `Vec<u32>` - Index (starting at 1) in the imposed sequence of each page in reading order.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{
+///     args::{Binding, BindingMethod, LayoutType},
+///     calc::generate_unbooklet_order,
+/// };
+///
+/// // Imposed as 8,1 | 2,7 | 6,3 | 4,5
+/// let order =
+///     generate_unbooklet_order(4, LayoutType::TwoUp, Binding::Ltr, BindingMethod::Saddle, &[]);
+/// assert_eq!(order, vec![2, 3, 6, 7, 8, 5, 4, 1]);
+/// ```
+pub fn generate_unbooklet_order(
+    sides: u32,
+    layout: LayoutType,
+    binding: Binding,
+    method: BindingMethod,
+    signatures: &[u32],
+) -> Vec<u32> {
+    let n = sides * layout.pages_per_side() as u32;
+    let whole = [sides / 2];
+    let signatures = if signatures.is_empty() {
+        &whole
+    } else {
+        signatures
+    };
+    let imposition = apply_binding(
+        generate_bound_imposition(n, layout, method, signatures),
+        layout,
+        binding,
+    );
+    let mut order = vec![0; n as usize];
+    for (index, page) in imposition.into_iter().enumerate() {
+        if (1..=n).contains(&page) {
+            order[page as usize - 1] = index as u32 + 1;
+        }
+    }
+    order
+}
+
+/// Returns whether the page at `index` of a bound imposition sequence must be rotated 180°.
+///
+/// Only top binding rotates pages: every slot on the back side of a sheet is turned upside
+/// down so that the sheet reads correctly when flipped on its long edge.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::{Binding, LayoutType}, calc::is_rotated_slot};
+///
+/// assert!(!is_rotated_slot(1, LayoutType::TwoUp, Binding::Top));
+/// assert!(is_rotated_slot(2, LayoutType::TwoUp, Binding::Top));
+/// assert!(!is_rotated_slot(2, LayoutType::TwoUp, Binding::Ltr));
+/// ```
+pub fn is_rotated_slot(index: usize, layout: LayoutType, binding: Binding) -> bool {
+    binding == Binding::Top && (index / layout.pages_per_side()) % 2 == 1
+}
+
+/// Returns the grid slot that lies behind `slot` once the sheet is turned around `axis`.
+///
+/// Slots are numbered left-to-right, top-to-bottom.
+///
+/// # Example
+/// ```
+/// use bookify_rs::calc::{mirror_grid_slot, FlipAxis};
+///
+/// // 3 columns, 2 rows
+/// assert_eq!(mirror_grid_slot(0, 3, 2, FlipAxis::Vertical), 2);
+/// assert_eq!(mirror_grid_slot(0, 3, 2, FlipAxis::Horizontal), 3);
+/// ```
+pub fn mirror_grid_slot(slot: u32, columns: u32, rows: u32, axis: FlipAxis) -> u32 {
+    let (row, column) = (slot / columns, slot % columns);
+    match axis {
+        FlipAxis::Vertical => row * columns + (columns - 1 - column),
+        FlipAxis::Horizontal => (rows - 1 - row) * columns + column,
+    }
+}
+
+/// Generates a step-and-repeat sequence filling the slots of a grid.
+///
+/// With `GridMode::Repeat` every page fills all slots of its own sheet; with
+/// `GridMode::Sequence` consecutive pages fill the slots one after the other.
+/// When `back_flip` is set, odd pages are fronts and even pages are backs: every front
+/// side is followed by its back side, whose slots are mirrored around the flip axis so
+/// that each back lands behind its front.
+///
+/// # Parameters
+/// * `n` - Total number of source pages
+/// * `columns`, `rows` - Grid size
+/// * `mode` - How pages fill the grid
+/// * `back_flip` - Axis the sheet is turned around for duplex printing, `None` for simplex
+///
+/// # Returns
+/// `Vec<u32>` - Slot contents sheet side after sheet side (left-to-right, top-to-bottom).
+///             0 represents an empty slot.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::GridMode, calc::{generate_grid_order, FlipAxis}};
+///
+/// let order = generate_grid_order(3, 2, 1, GridMode::Sequence, None);
+/// assert_eq!(order, vec![1, 2, 3, 0]);
+///
+/// let order = generate_grid_order(4, 2, 1, GridMode::Sequence, Some(FlipAxis::Vertical));
+/// assert_eq!(order, vec![1, 3, 4, 2]);
+/// ```
+pub fn generate_grid_order(
+    n: u32,
+    columns: u32,
+    rows: u32,
+    mode: GridMode,
+    back_flip: Option<FlipAxis>,
+) -> Vec<u32> {
+    let slots = columns * rows;
+
+    // Pages printed on fronts, and backs when duplex (0 when a front has no back)
+    let (fronts, backs): (Vec<u32>, Vec<u32>) = match back_flip {
+        None => ((1..=n).collect(), Vec::new()),
+        Some(_) => (
+            (1..=n).step_by(2).collect(),
+            (1..=n)
+                .step_by(2)
+                .map(|p| if p < n { p + 1 } else { 0 })
+                .collect(),
+        ),
+    };
+
+    // Content of each slot of each front side
+    let front_sides: Vec<Vec<u32>> = match mode {
+        GridMode::Repeat => fronts.iter().map(|&p| vec![p; slots as usize]).collect(),
+        GridMode::Sequence => fronts
+            .chunks(slots as usize)
+            .map(|chunk| {
+                let mut side = chunk.to_vec();
+                side.resize(slots as usize, 0);
+                side
+            })
+            .collect(),
+    };
+
+    let mut order = Vec::new();
+    for (side_index, front) in front_sides.iter().enumerate() {
+        order.extend_from_slice(front);
+        if let Some(axis) = back_flip {
+            // Back slot shows the back of the front slot it lies behind
+            for slot in 0..slots {
+                let front_slot = mirror_grid_slot(slot, columns, rows, axis);
+                let back = match mode {
+                    GridMode::Repeat => backs[side_index],
+                    GridMode::Sequence => backs
+                        .get(side_index * slots as usize + front_slot as usize)
+                        .copied()
+                        .unwrap_or(0),
+                };
+                order.push(back);
+            }
+        }
+    }
+    order
+}
+
+/// Generates the front/back page pairs of flashcards made from two documents.
+///
+/// The questions are pages `1..=questions` and the answers follow them, as pages
+/// `questions + 1..=questions + answers`. Each question is followed by its answer, ready for
+/// `generate_grid_order` in duplex mode. A missing question or answer becomes a blank page (0).
+///
+/// # Example
+/// ```
+/// use bookify_rs::calc::generate_flashcard_order;
+///
+/// assert_eq!(generate_flashcard_order(3, 2), vec![1, 4, 2, 5, 3, 0]);
+/// ```
+pub fn generate_flashcard_order(questions: u32, answers: u32) -> Vec<u32> {
+    (1..=questions.max(answers))
+        .flat_map(|i| {
+            let question = if i <= questions { i } else { 0 };
+            let answer = if i <= answers { questions + i } else { 0 };
+            [question, answer]
+        })
+        .collect()
+}
+
+/// Generates the panel order of brochures, outside then inside of each sheet.
+///
+/// Source pages are read in brochure order, every `fold.panels()` pages make one brochure:
+/// * `TriFold`: front cover, tuck-in flap, the three inside panels, back cover.
+///   Outside `[2, 6, 1]`, inside `[3, 4, 5]`.
+/// * `ZFold`: front cover, the three inside panels, then the two other outside panels.
+///   Outside `[5, 6, 1]`, inside `[2, 3, 4]`.
+/// * `GateFold`: front cover on the left and right gates, the four inside panels, back
+///   cover on the two center panels. Outside `[2, 7, 8, 1]`, inside `[3, 4, 5, 6]`.
+///
+/// Panels run left to right. The inside is laid out for a sheet flipped on its short edge.
+///
+/// # Parameters
+/// * `total_pages` - Total number of pages in the document
+/// * `fold` - Brochure fold
+///
+/// # Returns
+/// `Vec<u32>` - Page sequence, `fold.panels_per_side()` pages per sheet side.
+///                                     0 represents a blank page.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::FoldType, calc::generate_brochure_order};
+///
+/// let order = generate_brochure_order(7, FoldType::TriFold);
+/// assert_eq!(order, vec![2, 6, 1, 3, 4, 5, 0, 0, 7, 0, 0, 0]);
+/// ```
+pub fn generate_brochure_order(total_pages: u32, fold: FoldType) -> Vec<u32> {
+    let pattern: &[u32] = match fold {
+        FoldType::TriFold => &[2, 6, 1, 3, 4, 5],
+        FoldType::ZFold => &[5, 6, 1, 2, 3, 4],
+        FoldType::GateFold => &[2, 7, 8, 1, 3, 4, 5, 6],
+    };
+    let panels = fold.panels();
+    let brochures = total_pages.div_ceil(panels);
+
+    (0..brochures)
+        .flat_map(|brochure| {
+            pattern.iter().map(move |&panel| {
+                let page = brochure * panels + panel;
+                if page <= total_pages {
+                    page
+                } else {
+                    0
+                }
+            })
+        })
+        .collect()
+}
+
 /// Generates a page sequence for double-sided printing based on flip type and page selection.
 ///
 /// The function handles different printing scenarios by combining flip type (RR, NN, RN, NR)
@@ -156,6 +888,43 @@ pub fn generate_double_sided_order(
     pages
 }
 
+/// Restores the reading order of a document scanned as a stack of fronts and a stack of backs.
+///
+/// This is the inverse of [`generate_double_sided_order`]: the fronts hold the odd pages and
+/// the backs the even pages, each stack in the order given by the flip type. The fronts are
+/// pages `1..=fronts` of the input and the backs follow them. When there is one back fewer than
+/// fronts, the blank back of the last sheet was left out.
+///
+/// # Parameters
+/// * `fronts` - Number of scanned fronts
+/// * `backs` - Number of scanned backs, `fronts` or `fronts - 1`
+/// * `flip_type` - Which stacks are in reverse order (first letter fronts, second letter backs)
+///
+/// # Returns
+/// `Vec<u32>` - Input page of each page in reading order.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::FlipType, calc::generate_interleave_order};
+///
+/// // Fronts 1,3,5 in order, backs scanned last page first: 6,4,2
+/// assert_eq!(generate_interleave_order(3, 3, FlipType::NR), vec![1, 6, 2, 5, 3, 4]);
+/// ```
+pub fn generate_interleave_order(fronts: u32, backs: u32, flip_type: FlipType) -> Vec<u32> {
+    let total_pages = fronts + backs;
+    let mut order = vec![0; total_pages as usize];
+    let odd_pages = generate_double_sided_order(total_pages, flip_type, OddEven::Odd);
+    for (index, page) in odd_pages.into_iter().enumerate() {
+        order[page as usize - 1] = index as u32 + 1;
+    }
+    // A missing blank back is not in the backs stack
+    let even_pages = generate_double_sided_order(total_pages, flip_type, OddEven::Even);
+    for (index, page) in even_pages.into_iter().filter(|&page| page != 0).enumerate() {
+        order[page as usize - 1] = fronts + index as u32 + 1;
+    }
+    order
+}
+
 #[cfg(test)]
 mod tests {
     use super::*; // Import all items from parent module
@@ -243,6 +1012,420 @@ mod tests {
         assert_eq!(generate_booklet_imposition(6, LayoutType::TwoUp), expected);
     }
 
+    // --- MiniZine Layout Tests ---
+
+    #[test]
+    fn test_mini_zine_n_8_pages() {
+        let expected = vec![5, 4, 3, 2, 6, 7, 8, 1];
+        assert_eq!(
+            generate_booklet_imposition(8, LayoutType::MiniZine),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_mini_zine_n_10_pages() {
+        // Second sheet is its own zine, padded with blanks
+        let expected = vec![
+            5, 4, 3, 2, 6, 7, 8, 1, // Sheet 1
+            0, 0, 0, 10, 0, 0, 0, 9, // Sheet 2
+        ];
+        assert_eq!(
+            generate_booklet_imposition(10, LayoutType::MiniZine),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_mini_zine_rtl() {
+        let imposition = generate_booklet_imposition(8, LayoutType::MiniZine);
+        assert_eq!(
+            apply_binding(imposition, LayoutType::MiniZine, Binding::Rtl),
+            vec![2, 3, 4, 5, 1, 8, 7, 6]
+        );
+    }
+
+    // --- Signature Tests ---
+
+    #[test]
+    fn test_split_signatures_exact() {
+        assert_eq!(split_signatures(32, LayoutType::TwoUp, 4), vec![4, 4]);
+        assert_eq!(split_signatures(0, LayoutType::TwoUp, 4), vec![]);
+    }
+
+    #[test]
+    fn test_split_signatures_single() {
+        // Signature larger than the book
+        assert_eq!(split_signatures(13, LayoutType::FourUp, 8), vec![2]);
+    }
+
+    #[test]
+    fn test_single_signature_matches_booklet() {
+        let signatures = split_signatures(14, LayoutType::FourUp, 100);
+        assert_eq!(
+            generate_signature_imposition(14, LayoutType::FourUp, &signatures),
+            generate_booklet_imposition(14, LayoutType::FourUp)
+        );
+    }
+
+    #[test]
+    fn test_signature_imposition_two_up_n_16_pages() {
+        let expected = vec![
+            8, 1, 2, 7, 6, 3, 4, 5, // Signature 1
+            16, 9, 10, 15, 14, 11, 12, 13, // Signature 2
+        ];
+        assert_eq!(
+            generate_signature_imposition(16, LayoutType::TwoUp, &[2, 2]),
+            expected
+        );
+    }
+
+    // --- Perfect Binding Tests ---
+
+    #[test]
+    fn test_perfect_binding_two_up_n_6_pages() {
+        let expected = vec![
+            4, 1, 2, 3, // Folio 1
+            0, 5, 6, 0, // Folio 2, 8->0, 7->0
+        ];
+        assert_eq!(
+            generate_perfect_binding_imposition(6, LayoutType::TwoUp),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_perfect_binding_four_up_n_16_pages() {
+        let expected = vec![
+            4, 1, 8, 5, // Sheet 1, Side A: folios 1 and 2
+            2, 3, 6, 7, // Sheet 1, Side B
+            12, 9, 16, 13, // Sheet 2, Side A: folios 3 and 4
+            10, 11, 14, 15, // Sheet 2, Side B
+        ];
+        assert_eq!(
+            generate_perfect_binding_imposition(16, LayoutType::FourUp),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_perfect_binding_zero_pages() {
+        assert_eq!(
+            generate_perfect_binding_imposition(0, LayoutType::TwoUp),
+            vec![]
+        );
+    }
+
+    // --- Cut-and-stack Tests ---
+
+    #[test]
+    fn test_cut_stack_two_up_n_5_pages() {
+        // 2 sheets: left stack holds leaves 1-2, right stack leaves 3-4
+        let expected = vec![
+            1, 5, 0, 2, // Sheet 1
+            3, 0, 0, 4, // Sheet 2
+        ];
+        assert_eq!(
+            generate_cut_stack_imposition(5, LayoutType::TwoUp),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_cut_stack_four_up_n_16_pages() {
+        // 2 sheets, 4 stacks of 2 leaves each
+        let expected = vec![
+            1, 5, 9, 13, // Sheet 1, Side A
+            6, 2, 14, 10, // Sheet 1, Side B
+            3, 7, 11, 15, // Sheet 2, Side A
+            8, 4, 16, 12, // Sheet 2, Side B
+        ];
+        assert_eq!(
+            generate_cut_stack_imposition(16, LayoutType::FourUp),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_sheet_count() {
+        assert_eq!(sheet_count(0, LayoutType::TwoUp), 0);
+        assert_eq!(sheet_count(48, LayoutType::TwoUp), 12);
+        assert_eq!(sheet_count(49, LayoutType::TwoUp), 13);
+        assert_eq!(sheet_count(9, LayoutType::MiniZine), 2);
+    }
+
+    // --- Binding Direction Tests ---
+
+    #[test]
+    fn test_binding_ltr_unchanged() {
+        let imposition = generate_booklet_imposition(8, LayoutType::FourUp);
+        assert_eq!(
+            apply_binding(imposition.clone(), LayoutType::FourUp, Binding::Ltr),
+            imposition
+        );
+    }
+
+    #[test]
+    fn test_binding_rtl_two_up() {
+        // Sheet 1, Side A: 1, 8 / Side B: 7, 2
+        // Sheet 2, Side A: 3, 6 / Side B: 5, 4
+        let imposition = generate_booklet_imposition(8, LayoutType::TwoUp);
+        assert_eq!(
+            apply_binding(imposition, LayoutType::TwoUp, Binding::Rtl),
+            vec![1, 8, 7, 2, 3, 6, 5, 4]
+        );
+    }
+
+    #[test]
+    fn test_binding_rtl_four_up_with_blanks() {
+        // Based on n=5 result [0,1,0,3,2,0,4,5], swap each row
+        let imposition = generate_booklet_imposition(5, LayoutType::FourUp);
+        assert_eq!(
+            apply_binding(imposition, LayoutType::FourUp, Binding::Rtl),
+            vec![1, 0, 3, 0, 0, 2, 5, 4]
+        );
+    }
+
+    #[test]
+    fn test_binding_top_two_up() {
+        // Sheet 1, Side A: 8 above 1 / Side B (upside down): 7 above 2
+        // Sheet 2, Side A: 6 above 3 / Side B (upside down): 5 above 4
+        let imposition = generate_booklet_imposition(8, LayoutType::TwoUp);
+        assert_eq!(
+            apply_binding(imposition, LayoutType::TwoUp, Binding::Top),
+            vec![8, 1, 7, 2, 6, 3, 5, 4]
+        );
+    }
+
+    #[test]
+    fn test_binding_top_four_up() {
+        // Side A: columns (8 above 1) and (6 above 3)
+        // Side B: columns (2 above 7) and (4 above 5), turned upside down
+        let imposition = generate_booklet_imposition(8, LayoutType::FourUp);
+        assert_eq!(
+            apply_binding(imposition, LayoutType::FourUp, Binding::Top),
+            vec![8, 6, 1, 3, 5, 7, 4, 2]
+        );
+    }
+
+    #[test]
+    fn test_rotated_slots_top_binding() {
+        let rotated: Vec<bool> = (0..8)
+            .map(|i| is_rotated_slot(i, LayoutType::FourUp, Binding::Top))
+            .collect();
+        assert_eq!(
+            rotated,
+            vec![false, false, false, false, true, true, true, true]
+        );
+        assert!((0..8).all(|i| !is_rotated_slot(i, LayoutType::FourUp, Binding::Rtl)));
+    }
+
+    // --- Grid Tests ---
+
+    #[test]
+    fn test_grid_repeat_simplex() {
+        let order = generate_grid_order(2, 2, 2, GridMode::Repeat, None);
+        assert_eq!(order, vec![1, 1, 1, 1, 2, 2, 2, 2]);
+    }
+
+    #[test]
+    fn test_grid_repeat_duplex() {
+        // Pages 1/2 and 3/4 are front/back pairs, page 5 has no back
+        let order = generate_grid_order(5, 2, 1, GridMode::Repeat, Some(FlipAxis::Vertical));
+        assert_eq!(order, vec![1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 0, 0]);
+    }
+
+    #[test]
+    fn test_grid_sequence_duplex_vertical() {
+        // 2x2 grid, fronts 1,3,5,7 and backs 2,4,6,8
+        let order = generate_grid_order(8, 2, 2, GridMode::Sequence, Some(FlipAxis::Vertical));
+        assert_eq!(order, vec![1, 3, 5, 7, 4, 2, 8, 6]);
+    }
+
+    #[test]
+    fn test_grid_sequence_duplex_horizontal() {
+        let order = generate_grid_order(8, 2, 2, GridMode::Sequence, Some(FlipAxis::Horizontal));
+        assert_eq!(order, vec![1, 3, 5, 7, 6, 8, 2, 4]);
+    }
+
+    #[test]
+    fn test_grid_sequence_padding() {
+        let order = generate_grid_order(5, 2, 2, GridMode::Sequence, None);
+        assert_eq!(order, vec![1, 2, 3, 4, 5, 0, 0, 0]);
+        assert_eq!(
+            generate_grid_order(0, 2, 2, GridMode::Sequence, None),
+            vec![]
+        );
+    }
+
+    // --- Brochure Tests ---
+
+    #[test]
+    fn test_brochure_order_gate_fold() {
+        assert_eq!(
+            generate_brochure_order(8, FoldType::GateFold),
+            vec![2, 7, 8, 1, 3, 4, 5, 6]
+        );
+    }
+
+    #[test]
+    fn test_brochure_order_z_fold_two_sheets() {
+        let order = generate_brochure_order(12, FoldType::ZFold);
+        assert_eq!(order[6..], [11, 12, 7, 8, 9, 10]);
+    }
+
+    // --- Signature Balance Tests ---
+
+    #[test]
+    fn test_balance_signatures_fits_in_one() {
+        assert_eq!(balance_signatures(13, LayoutType::FourUp, 12), vec![2]);
+        assert_eq!(balance_signatures(0, LayoutType::FourUp, 12), vec![]);
+    }
+
+    #[test]
+    fn test_balance_signatures_limit_one_sheet() {
+        assert_eq!(balance_signatures(12, LayoutType::TwoUp, 1), vec![1, 1, 1]);
+    }
+
+    // --- Padding Tests ---
+
+    #[test]
+    fn test_pad_pages_end_matches_booklet_padding() {
+        assert_eq!(pad_pages(3, 4, &BlankPosition::End), vec![1, 2, 3, 0]);
+        assert_eq!(
+            pad_pages(4, 4, &BlankPosition::BeforeLast),
+            vec![1, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn test_pad_pages_after_first() {
+        assert_eq!(
+            pad_pages(13, 16, &BlankPosition::AfterFirst),
+            vec![1, 0, 0, 0, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13]
+        );
+    }
+
+    #[test]
+    fn test_pad_pages_custom_list_clamped() {
+        assert_eq!(
+            pad_pages(2, 4, &BlankPosition::After(vec![0, 9, 1])),
+            vec![0, 1, 2, 0]
+        );
+    }
+
+    // --- Recto Padding Tests ---
+
+    #[test]
+    fn test_recto_padding_consecutive_chapters() {
+        assert_eq!(
+            generate_recto_padding(4, &[2, 3, 4]),
+            vec![1, 0, 2, 0, 3, 0, 4]
+        );
+    }
+
+    #[test]
+    fn test_recto_padding_ignores_invalid_starts() {
+        assert_eq!(generate_recto_padding(3, &[0, 1, 9]), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_interleave_inverts_double_sided_order() {
+        for flip_type in [FlipType::RR, FlipType::NN, FlipType::RN, FlipType::NR] {
+            for total_pages in [6, 7] {
+                let odd = generate_double_sided_order(total_pages, flip_type, OddEven::Odd);
+                let even = generate_double_sided_order(total_pages, flip_type, OddEven::Even);
+                let scanned: Vec<u32> = odd
+                    .into_iter()
+                    .chain(even.into_iter().filter(|&page| page != 0))
+                    .collect();
+                let fronts = total_pages.div_ceil(2);
+                let order = generate_interleave_order(fronts, total_pages - fronts, flip_type);
+                let restored: Vec<u32> = order.iter().map(|&i| scanned[i as usize - 1]).collect();
+                assert_eq!(restored, (1..=total_pages).collect::<Vec<u32>>());
+            }
+        }
+    }
+
+    #[test]
+    fn test_unbooklet_inverts_booklet_imposition() {
+        for layout in [LayoutType::TwoUp, LayoutType::FourUp] {
+            for binding in [Binding::Ltr, Binding::Rtl, Binding::Top] {
+                let n = 4 * layout.pages_per_sheet();
+                let imposition =
+                    apply_binding(generate_booklet_imposition(n, layout), layout, binding);
+                let sides = n / layout.pages_per_side() as u32;
+                let restored: Vec<u32> =
+                    generate_unbooklet_order(sides, layout, binding, BindingMethod::Saddle, &[])
+                        .into_iter()
+                        .map(|index| imposition[index as usize - 1])
+                        .collect();
+                assert_eq!(restored, (1..=n).collect::<Vec<u32>>());
+            }
+        }
+    }
+
+    #[test]
+    fn test_unbooklet_inverts_bound_imposition() {
+        let layout = LayoutType::FourUp;
+        let n = 5 * layout.pages_per_sheet();
+        let sides = n / layout.pages_per_side() as u32;
+        for (method, signatures) in [
+            (BindingMethod::Saddle, vec![3, 2]),
+            (BindingMethod::Perfect, vec![5]),
+            (BindingMethod::CutStack, vec![5]),
+        ] {
+            let imposition = apply_binding(
+                generate_bound_imposition(n, layout, method, &signatures),
+                layout,
+                Binding::Ltr,
+            );
+            let restored: Vec<u32> =
+                generate_unbooklet_order(sides, layout, Binding::Ltr, method, &signatures)
+                    .into_iter()
+                    .map(|index| imposition[index as usize - 1])
+                    .collect();
+            assert_eq!(restored, (1..=n).collect::<Vec<u32>>());
+        }
+    }
+
+    #[test]
+    fn test_blank_insertion_at_both_ends() {
+        assert_eq!(generate_blank_insertion(2, &[2, 0]), vec![0, 1, 2, 0]);
+        assert_eq!(generate_blank_insertion(2, &[]), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_document_start_blanks() {
+        assert_eq!(document_start_blanks(4, DocumentStart::Any), 0);
+        assert_eq!(document_start_blanks(4, DocumentStart::Odd), 0);
+        assert_eq!(document_start_blanks(4, DocumentStart::Even), 1);
+        assert_eq!(document_start_blanks(5, DocumentStart::Any), 0);
+    }
+
+    // --- Page Index Tests ---
+
+    #[test]
+    fn test_resolve_page_index_bounds() {
+        assert_eq!(resolve_page_index(1, 4), Some(1));
+        assert_eq!(resolve_page_index(-4, 4), Some(1));
+        assert_eq!(resolve_page_index(0, 4), None);
+        assert_eq!(resolve_page_index(5, 4), None);
+    }
+
+    // --- Flashcard Tests ---
+
+    #[test]
+    fn test_flashcard_order_equal() {
+        assert_eq!(generate_flashcard_order(2, 2), vec![1, 3, 2, 4]);
+    }
+
+    #[test]
+    fn test_flashcard_order_missing_question() {
+        assert_eq!(generate_flashcard_order(1, 2), vec![1, 2, 0, 3]);
+        assert_eq!(generate_flashcard_order(0, 0), vec![]);
+    }
+
     // --- Double-sided Order Tests ---
 
     #[test]
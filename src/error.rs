@@ -28,6 +28,10 @@ pub enum BookifyError {
     #[error("PDF processing failed: {operation} - {details}")]
     PdfProcessingFailed { operation: String, details: String },
 
+    /// Invalid or unsupported combination of arguments
+    #[error("Invalid argument: {message}")]
+    InvalidArgument { message: String },
+
     /// Other error with context
     #[error("Other error: {context} - {message}")]
     Other { context: String, message: String },
@@ -62,6 +66,13 @@ impl BookifyError {
         }
     }
 
+    /// Create an invalid argument error
+    pub fn invalid_argument(message: impl Into<String>) -> Self {
+        Self::InvalidArgument {
+            message: message.into(),
+        }
+    }
+
     /// Create an other error with context
     pub fn other(context: impl Into<String>, message: impl Into<String>) -> Self {
         Self::Other {
@@ -1,33 +1,139 @@
-use std::{collections::BTreeMap, path::PathBuf};
+use std::{
+    collections::{btree_map::Entry, BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
 
 use crate::{
-    args::{FlipType, LayoutType, OddEven},
-    calc::{generate_booklet_imposition, generate_double_sided_order},
+    annotations::{annotation_appearances, place_annotations},
+    args::{
+        AnnotationMode, Binding, BindingMethod, BlankPosition, Duplex, FlipType, FoldType,
+        GridMode, LayoutType, OddEven, PageSelection, PaperSize, SignatureSize,
+    },
+    calc::{
+        apply_binding, balance_signatures, generate_blank_insertion, generate_bound_imposition,
+        generate_brochure_order, generate_double_sided_order, generate_flashcard_order,
+        generate_grid_order, generate_interleave_order, generate_recto_padding,
+        generate_unbooklet_order, is_rotated_slot, pad_pages, sheet_count, split_signatures,
+        FlipAxis,
+    },
     error::BookifyError,
+    marks::{mark_font, marks_operations, Mark, MARK_FONT_NAME},
+    metadata::{
+        has_composed_pages, read_imposition, reproducible_timestamp, write_content_id,
+        write_imposition, write_provenance, Imposition, Job, Provenance, Source,
+    },
+    navigation::{
+        destination_page, remap_page_labels, remap_page_references, remove_page_labels, PageMove,
+        PageMoves,
+    },
+    sheet::{
+        booklet_piece, booklet_sides, brochure_sides, cover_spread, grid_sheet, handout_sides,
+        mini_zine_sheet, poster_sheets, CoverSpec, GridSpec, HandoutSpec, PosterSpec, SheetLayout,
+        Slot,
+    },
+};
+use chrono::Utc;
+use lopdf::{
+    content::{Content, Operation},
+    Dictionary, Document, Object, ObjectId, Stream,
 };
-use lopdf::{Dictionary, Document, Object, ObjectId, Stream};
+
+/// Page attributes that may be inherited from the page tree
+const INHERITABLE_PAGE_ATTRIBUTES: [&[u8]; 4] = [b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+
+/// Get an inherited page attribute, walking up the page tree
+fn inherited_attribute<'a>(doc: &'a Document, page_id: ObjectId, key: &[u8]) -> Option<&'a Object> {
+    let mut node_id = Some(page_id);
+    while let Some(id) = node_id {
+        let node = doc.get_dictionary(id).ok()?;
+        if let Ok(value) = node.get(key) {
+            return Some(value);
+        }
+        node_id = node.get(b"Parent").and_then(Object::as_reference).ok();
+    }
+    None
+}
+
+/// Settings of a booklet imposition
+#[derive(Clone, Debug, PartialEq)]
+pub struct BookletSpec {
+    pub layout: LayoutType,
+    /// Binding edge
+    pub binding: Binding,
+    pub method: BindingMethod,
+    /// Signatures of a saddle-stitched booklet, `None` for a single signature
+    pub signature_size: Option<SignatureSize>,
+    /// Most sheets of an automatic signature
+    pub saddle_sheet_limit: u32,
+    /// Compose printed sheets with printer's marks
+    pub marks: bool,
+    /// Where blank pages padding the booklet to whole sheets go
+    pub blank_position: BlankPosition,
+}
+
+impl BookletSpec {
+    /// Create a saddle-stitched booklet of a single signature, without marks, padded at the end
+    pub fn new(layout: LayoutType, binding: Binding) -> Self {
+        Self {
+            layout,
+            binding,
+            method: BindingMethod::Saddle,
+            signature_size: None,
+            saddle_sheet_limit: 12,
+            marks: false,
+            blank_position: BlankPosition::End,
+        }
+    }
+}
 
 /// PDF Document Imposer
 pub struct PdfImposer {
     doc: Document,
     page_size: (f32, f32),
     total_pages: u32,
+    /// Form XObjects already created from source pages
+    page_forms: BTreeMap<ObjectId, ObjectId>,
+    /// Imposition recorded in the input, replaced by the one made by an export
+    imposition: Option<Imposition>,
+    /// Input files, in the order they were loaded
+    sources: Vec<Source>,
+    /// Job recorded in the output
+    job: Job,
+    /// Whether saving produces byte-identical output for the same job
+    reproducible: bool,
+    /// What happens to the annotations of pages placed on sheets
+    annotations: AnnotationMode,
 }
 
 impl PdfImposer {
     /// Create new PdfImposer instance
     pub fn new(input_path: PathBuf) -> Result<Self, BookifyError> {
-        let doc = Document::load(&input_path)?;
+        let (doc, source) = Self::load_document(&input_path)?;
         let page_size = Self::get_page_size(&doc)?;
         let pages_map: BTreeMap<u32, ObjectId> = doc.get_pages();
         let total_pages = pages_map.len() as u32;
+        let imposition = read_imposition(&doc);
         Ok(Self {
             doc,
             page_size,
             total_pages,
+            page_forms: BTreeMap::new(),
+            imposition,
+            sources: vec![source],
+            job: Job::default(),
+            reproducible: false,
+            annotations: AnnotationMode::Keep,
         })
     }
 
+    /// Load a PDF file, describing it as a source of the output
+    fn load_document(input_path: &Path) -> Result<(Document, Source), BookifyError> {
+        let content = fs::read(input_path).map_err(|e| BookifyError::io_error(e, input_path))?;
+        let doc = Document::load_mem(&content)?;
+        Ok((doc, Source::new(input_path, &content)))
+    }
+
     /// Get document page size from the first page
     fn get_page_size(doc: &Document) -> Result<(f32, f32), BookifyError> {
         let pages = doc.get_pages();
@@ -76,6 +182,107 @@ impl PdfImposer {
         Ok((width, height))
     }
 
+    /// Get page size of the document, taken from its first page
+    pub fn page_size(&self) -> (f32, f32) {
+        self.page_size
+    }
+
+    /// Get total number of pages in the document
+    pub fn total_pages(&self) -> u32 {
+        self.total_pages
+    }
+
+    /// Set the job recorded in the output
+    pub fn set_job(&mut self, job: Job) {
+        self.job = job;
+    }
+
+    /// Save byte-identical output for the same input and job: timestamps come from
+    /// `SOURCE_DATE_EPOCH`, objects are numbered in document order and the
+    /// trailer /ID is derived from the content
+    pub fn set_reproducible(&mut self, reproducible: bool) {
+        self.reproducible = reproducible;
+    }
+
+    /// Set what happens to the annotations of pages placed on sheets
+    pub fn set_annotations(&mut self, annotations: AnnotationMode) {
+        self.annotations = annotations;
+    }
+
+    /// Imposition recorded in the input, or made by the last export
+    pub fn imposition(&self) -> Option<&Imposition> {
+        self.imposition.as_ref()
+    }
+
+    /// Refuse a document that is already imposed, by this tool or by another one whose
+    /// pages place several pages each
+    pub fn ensure_not_imposed(&self) -> Result<(), BookifyError> {
+        if let Some(imposition) = &self.imposition {
+            let undo = match (imposition.kind.as_str(), imposition.layout) {
+                ("booklet", Some(LayoutType::TwoUp | LayoutType::FourUp)) if imposition.sheets => {
+                    "run `bookify-rs unbooklet` on it to recover the pages"
+                }
+                _ => "impose the original document instead",
+            };
+            return Err(BookifyError::invalid_argument(format!(
+                "Input is already imposed: {}; {}, or use --force to impose it again",
+                imposition.describe(),
+                undo
+            )));
+        }
+        if has_composed_pages(&self.doc) {
+            return Err(BookifyError::invalid_argument(
+                "Input looks already imposed, every page places several pages; \
+                 use --force to impose it anyway",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Refuse a document already ordered for double-sided printing. Booklets and imposed
+    /// sheets are accepted, they are meant to be printed on both sides.
+    pub fn ensure_not_double_sided(&self) -> Result<(), BookifyError> {
+        match &self.imposition {
+            Some(imposition) if imposition.kind == "double-sided" => {
+                Err(BookifyError::invalid_argument(format!(
+                    "Input is already ordered for double-sided printing: {}; \
+                     print the original document instead, or use --force to reorder it again",
+                    imposition.describe()
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Get binding direction from the document /ViewerPreferences /Direction entry
+    pub fn document_binding(&self) -> Option<Binding> {
+        let catalog = self.doc.catalog().ok()?;
+        let preferences = catalog.get(b"ViewerPreferences").ok()?;
+        let (_, preferences) = self.doc.dereference(preferences).ok()?;
+        let direction = preferences.as_dict().ok()?.get(b"Direction").ok()?;
+        match direction.as_name().ok()? {
+            b"R2L" => Some(Binding::Rtl),
+            b"L2R" => Some(Binding::Ltr),
+            _ => None,
+        }
+    }
+
+    /// Get page rotation, following inherited /Rotate entries of the page tree
+    fn page_rotation(&self, page_id: ObjectId) -> i64 {
+        self.inherited_page_attribute(page_id, b"Rotate")
+            .and_then(|rotate| rotate.as_i64().ok())
+            .unwrap_or(0)
+    }
+
+    /// Rotate page clockwise by angle, which must be a multiple of 90
+    fn rotate_page(&mut self, page_id: ObjectId, angle: i64) -> Result<(), BookifyError> {
+        let rotation = (self.page_rotation(page_id) + angle).rem_euclid(360);
+        self.doc
+            .get_dictionary_mut(page_id)?
+            .set(b"Rotate", Object::Integer(rotation));
+        Ok(())
+    }
+
     /// Create blank page with page size
     fn create_blank_page(&mut self) -> Result<ObjectId, BookifyError> {
         let mut page_dict = Dictionary::new();
@@ -164,6 +371,19 @@ impl PdfImposer {
         Ok(())
     }
 
+    /// Create a copy of a page sharing its content, with its inherited attributes
+    fn duplicate_page(&mut self, page_id: ObjectId) -> Result<ObjectId, BookifyError> {
+        let mut page = self.doc.get_dictionary(page_id)?.clone();
+        for key in INHERITABLE_PAGE_ATTRIBUTES {
+            if !page.has(key) {
+                if let Some(value) = inherited_attribute(&self.doc, page_id, key) {
+                    page.set(key, value.clone());
+                }
+            }
+        }
+        Ok(self.doc.add_object(Object::Dictionary(page)))
+    }
+
     /// Create new page objects array based on page order
     fn create_new_kids_objects(
         &mut self,
@@ -171,11 +391,18 @@ impl PdfImposer {
         pages_map: &BTreeMap<u32, ObjectId>,
     ) -> Result<Vec<Object>, BookifyError> {
         let mut new_kids_objects: Vec<Object> = Vec::with_capacity(page_order.len());
+        let mut used_pages = BTreeSet::new();
         for &page_num in page_order {
             if page_num == 0 {
                 let blank_page_id = self.create_blank_page()?;
                 new_kids_objects.push(Object::Reference(blank_page_id));
             } else if let Some(&page_id) = pages_map.get(&page_num) {
+                // A page object may only appear once in the page tree
+                let page_id = if used_pages.insert(page_num) {
+                    page_id
+                } else {
+                    self.duplicate_page(page_id)?
+                };
                 new_kids_objects.push(Object::Reference(page_id));
             } else {
                 return Err(BookifyError::pdf_processing_failed(
@@ -231,13 +458,630 @@ impl PdfImposer {
         let new_kids_objects = self.create_new_kids_objects(&new_order, &pages_map)?;
         self.update_document_pages(new_kids_objects, new_order.len() as u32)?;
         self.validate_page_tree()?;
+        self.total_pages = new_order.len() as u32;
+
+        // Pages keep their page object the first time they are used
+        let moves: PageMoves = new_order
+            .iter()
+            .filter_map(|page_num| pages_map.get(page_num))
+            .map(|&page_id| {
+                let page_move = PageMove {
+                    page: page_id,
+                    matrix: None,
+                };
+                (page_id, page_move)
+            })
+            .collect();
+        remap_page_references(&mut self.doc, &moves)?;
+        remap_page_labels(&mut self.doc, &new_order)
+    }
+
+    /// Get the first page of every top-level outline item, in outline order
+    pub fn outline_chapter_starts(&self) -> Vec<u32> {
+        let page_numbers: BTreeMap<ObjectId, u32> = self
+            .doc
+            .get_pages()
+            .into_iter()
+            .map(|(page_num, page_id)| (page_id, page_num))
+            .collect();
+        let first_item = self
+            .doc
+            .catalog()
+            .and_then(|catalog| catalog.get(b"Outlines"))
+            .and_then(|outlines| self.doc.dereference(outlines))
+            .and_then(|(_, outlines)| outlines.as_dict())
+            .and_then(|outlines| outlines.get(b"First"))
+            .and_then(Object::as_reference)
+            .ok();
+
+        let mut starts = Vec::new();
+        let mut visited = Vec::new();
+        let mut item_id = first_item;
+        while let Some(id) = item_id.filter(|id| !visited.contains(id)) {
+            visited.push(id);
+            let Ok(item) = self.doc.get_dictionary(id) else {
+                break;
+            };
+            let dest = item
+                .get(b"Dest")
+                .or_else(|_| item.get(b"A"))
+                .ok()
+                .and_then(|dest| destination_page(&self.doc, dest));
+            if let Some(page_num) = dest.and_then(|page_id| page_numbers.get(&page_id)) {
+                starts.push(*page_num);
+            }
+            item_id = item.get(b"Next").and_then(Object::as_reference).ok();
+        }
+        starts
+    }
+
+    /// Insert blank pages so every chapter starts on a recto page.
+    /// Returns the number of blank pages added.
+    pub fn pad_chapters_to_recto(&mut self, chapter_starts: &[u32]) -> Result<u32, BookifyError> {
+        let new_order = generate_recto_padding(self.total_pages, chapter_starts);
+        let blanks = new_order.len() as u32 - self.total_pages;
+        if blanks > 0 {
+            self.generate_new_pdf(new_order)?;
+        }
+        Ok(blanks)
+    }
+
+    /// Keep only the selected pages, in selection order
+    pub fn select_pages(&mut self, selection: &PageSelection) -> Result<(), BookifyError> {
+        let pages = selection
+            .resolve(self.total_pages)
+            .map_err(BookifyError::invalid_argument)?;
+        if pages.is_empty() {
+            return Err(BookifyError::invalid_argument("Page selection is empty"));
+        }
+        self.generate_new_pdf(pages)?;
+        self.page_size = Self::get_page_size(&self.doc)?;
         Ok(())
     }
 
-    /// Export booklet PDF
-    pub fn export_booklet(&mut self, layout: LayoutType) -> Result<(), BookifyError> {
-        let new_order = generate_booklet_imposition(self.total_pages, layout);
-        self.generate_new_pdf(new_order)
+    /// Move the given pages, in the given order, out of this document into a new imposer
+    pub fn split_off_pages(&mut self, pages: &[u32]) -> Result<PdfImposer, BookifyError> {
+        for (index, &page) in pages.iter().enumerate() {
+            if page == 0 || page > self.total_pages || pages[..index].contains(&page) {
+                return Err(BookifyError::invalid_argument(format!(
+                    "Page {} cannot be taken out of a {}-page document",
+                    page, self.total_pages
+                )));
+            }
+        }
+        let remaining: Vec<u32> = (1..=self.total_pages)
+            .filter(|page| !pages.contains(page))
+            .collect();
+        if remaining.is_empty() {
+            return Err(BookifyError::invalid_argument(
+                "Taking these pages out leaves an empty document",
+            ));
+        }
+
+        let mut split = PdfImposer {
+            doc: self.doc.clone(),
+            page_size: self.page_size,
+            total_pages: self.total_pages,
+            page_forms: BTreeMap::new(),
+            imposition: self.imposition.clone(),
+            sources: self.sources.clone(),
+            job: self.job.clone(),
+            reproducible: self.reproducible,
+            annotations: self.annotations,
+        };
+        split.generate_new_pdf(pages.to_vec())?;
+        split.page_size = Self::get_page_size(&split.doc)?;
+        split.doc.prune_objects();
+
+        // Drop the objects of the pages taken out, so later splits copy less
+        self.generate_new_pdf(remaining)?;
+        self.page_size = Self::get_page_size(&self.doc)?;
+        self.doc.prune_objects();
+        self.page_forms.clear();
+        Ok(split)
+    }
+
+    /// Split into parts of `every` pages, the last part may be shorter.
+    /// Parts are taken out one at a time as the iterator advances, so each one can be
+    /// saved and dropped before the next is built.
+    pub fn split_every(
+        self,
+        every: u32,
+    ) -> Result<impl Iterator<Item = Result<PdfImposer, BookifyError>>, BookifyError> {
+        if every == 0 {
+            return Err(BookifyError::invalid_argument(
+                "Parts need at least one page",
+            ));
+        }
+        let mut rest = Some(self);
+        Ok(std::iter::from_fn(move || {
+            let mut imposer = rest.take()?;
+            if imposer.total_pages <= every {
+                return Some(Ok(imposer));
+            }
+            let part: Vec<u32> = (1..=every).collect();
+            let split = imposer.split_off_pages(&part);
+            if split.is_ok() {
+                rest = Some(imposer);
+            }
+            Some(split)
+        }))
+    }
+
+    /// Rotate the given pages clockwise by angle, which must be a multiple of 90
+    pub fn rotate_pages(&mut self, pages: &[u32], angle: i64) -> Result<(), BookifyError> {
+        if angle % 90 != 0 {
+            return Err(BookifyError::invalid_argument(format!(
+                "Rotation angle {} is not a multiple of 90",
+                angle
+            )));
+        }
+        let pages_map = self.doc.get_pages();
+        // A page selected several times is still rotated once
+        let pages: BTreeSet<u32> = pages.iter().copied().collect();
+        for page in pages {
+            let &page_id = pages_map.get(&page).ok_or_else(|| {
+                BookifyError::invalid_argument(format!(
+                    "Page {} is outside of a {}-page document",
+                    page, self.total_pages
+                ))
+            })?;
+            self.rotate_page(page_id, angle)?;
+        }
+        Ok(())
+    }
+
+    /// Insert a blank page after each of the given pages, 0 inserts before the first page
+    pub fn insert_blank_pages(&mut self, after: &[u32]) -> Result<(), BookifyError> {
+        if let Some(&page) = after.iter().find(|&&page| page > self.total_pages) {
+            return Err(BookifyError::invalid_argument(format!(
+                "Page {} is outside of a {}-page document",
+                page, self.total_pages
+            )));
+        }
+        self.generate_new_pdf(generate_blank_insertion(self.total_pages, after))
+    }
+
+    /// Append blank pages after the last page
+    pub fn append_blank_pages(&mut self, count: u32) -> Result<(), BookifyError> {
+        if count > 0 {
+            let new_order = (1..=self.total_pages)
+                .chain(std::iter::repeat_n(0, count as usize))
+                .collect();
+            self.generate_new_pdf(new_order)?;
+        }
+        Ok(())
+    }
+
+    /// Append all pages of another PDF after the pages of this document.
+    /// Returns the number of appended pages.
+    pub fn append_document(&mut self, input_path: PathBuf) -> Result<u32, BookifyError> {
+        let (mut other, source) = Self::load_document(&input_path)?;
+        self.sources.push(source);
+        other.renumber_objects_with(self.doc.max_id + 1);
+
+        // Pages leave their page tree, so inherited attributes must move onto them
+        let pages_dict_id = self.doc.catalog()?.get(b"Pages")?.as_reference()?;
+        let other_pages: Vec<ObjectId> = other.get_pages().into_values().collect();
+        for &page_id in &other_pages {
+            let inherited: Vec<(&[u8], Object)> = INHERITABLE_PAGE_ATTRIBUTES
+                .iter()
+                .filter_map(|&key| {
+                    inherited_attribute(&other, page_id, key).map(|value| (key, value.clone()))
+                })
+                .collect();
+            let page = other.get_dictionary_mut(page_id)?;
+            for (key, value) in inherited {
+                page.set(key, value);
+            }
+            page.set(b"Parent", Object::Reference(pages_dict_id));
+        }
+
+        // Catalog and page tree nodes of the other document are not needed
+        for (id, object) in other.objects {
+            if !matches!(object.type_name(), Ok("Catalog") | Ok("Pages")) {
+                self.doc.objects.insert(id, object);
+            }
+        }
+        self.doc.max_id = self.doc.max_id.max(other.max_id);
+
+        let pages_dict = self.doc.get_dictionary_mut(pages_dict_id)?;
+        let page_count = pages_dict.get(b"Count").and_then(Object::as_i64)?;
+        let kids = pages_dict.get_mut(b"Kids")?.as_array_mut()?;
+        kids.extend(other_pages.iter().map(|&id| Object::Reference(id)));
+        pages_dict.set(
+            b"Count",
+            Object::Integer(page_count + other_pages.len() as i64),
+        );
+
+        self.total_pages += other_pages.len() as u32;
+        Ok(other_pages.len() as u32)
+    }
+
+    /// Get an inherited page attribute, walking up the page tree
+    fn inherited_page_attribute(&self, page_id: ObjectId, key: &[u8]) -> Option<&Object> {
+        inherited_attribute(&self.doc, page_id, key)
+    }
+
+    /// Get visible page box, CropBox if present, otherwise MediaBox
+    fn page_box(&self, page_id: ObjectId) -> Result<[f32; 4], BookifyError> {
+        let page_box = self
+            .inherited_page_attribute(page_id, b"CropBox")
+            .or_else(|| self.inherited_page_attribute(page_id, b"MediaBox"))
+            .ok_or_else(|| {
+                BookifyError::pdf_processing_failed(
+                    "Getting page box",
+                    "Failed to get MediaBox property",
+                )
+            })?;
+        let (_, page_box) = self.doc.dereference(page_box)?;
+        let values = page_box
+            .as_array()?
+            .iter()
+            .map(|value| self.doc.dereference(value).and_then(|(_, v)| v.as_float()))
+            .collect::<Result<Vec<f32>, _>>()?;
+        match values[..] {
+            [x0, y0, x1, y1] => Ok([x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1)]),
+            _ => Err(BookifyError::invalid_pdf_format(
+                "Page box is not a valid rectangle",
+            )),
+        }
+    }
+
+    /// Get page size as displayed, after its rotation
+    fn displayed_page_size(&self, page_id: ObjectId) -> Result<(f32, f32), BookifyError> {
+        let [x0, y0, x1, y1] = self.page_box(page_id)?;
+        Ok(match self.page_rotation(page_id).rem_euclid(360) {
+            90 | 270 => (y1 - y0, x1 - x0),
+            _ => (x1 - x0, y1 - y0),
+        })
+    }
+
+    /// Convert page into Form XObject so it can be placed on a sheet
+    fn page_to_form(&mut self, page_id: ObjectId) -> Result<ObjectId, BookifyError> {
+        if let Some(&form_id) = self.page_forms.get(&page_id) {
+            return Ok(form_id);
+        }
+
+        let page_box = self.page_box(page_id)?;
+        let content = self.doc.get_page_content(page_id)?;
+        let resources = self
+            .inherited_page_attribute(page_id, b"Resources")
+            .cloned();
+        let group = self
+            .doc
+            .get_dictionary(page_id)?
+            .get(b"Group")
+            .ok()
+            .cloned();
+
+        let mut form = lopdf::xobject::form(
+            page_box.to_vec(),
+            vec![1.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+            content,
+        );
+        if let Some(resources) = resources {
+            form.dict.set(b"Resources", resources);
+        }
+        if let Some(group) = group {
+            form.dict.set(b"Group", group);
+        }
+
+        let form_id = self.doc.add_object(Object::Stream(form));
+        self.page_forms.insert(page_id, form_id);
+        Ok(form_id)
+    }
+
+    /// Matrix placing a page into a slot
+    fn slot_matrix(&self, page_id: ObjectId, slot: &Slot) -> Result<[f32; 6], BookifyError> {
+        let page_box = self.page_box(page_id)?;
+        Ok(slot.placement_matrix(page_box, self.page_rotation(page_id)))
+    }
+
+    /// Create sheet page placing the given pages into their slots, then drawing the marks
+    fn create_sheet_page(
+        &mut self,
+        sheet: &SheetLayout,
+        placements: &[(ObjectId, Slot)],
+    ) -> Result<ObjectId, BookifyError> {
+        // Copied annotations refer to the sheet
+        let sheet_id = self.doc.new_object_id();
+        let mut xobjects = Dictionary::new();
+        let mut annots = Vec::new();
+        let mut operations = Vec::new();
+        for (index, &(page_id, slot)) in placements.iter().enumerate() {
+            let form_id = self.page_to_form(page_id)?;
+            let matrix = self.slot_matrix(page_id, &slot)?;
+            let name = format!("P{}", index);
+
+            xobjects.set(name.as_bytes(), Object::Reference(form_id));
+            operations.push(Operation::new("q", vec![]));
+            if let Some([x0, y0, x1, y1]) = slot.clip {
+                operations.push(Operation::new(
+                    "re",
+                    vec![x0.into(), y0.into(), (x1 - x0).into(), (y1 - y0).into()],
+                ));
+                operations.push(Operation::new("W", vec![]));
+                operations.push(Operation::new("n", vec![]));
+            }
+            operations.push(Operation::new(
+                "cm",
+                matrix.iter().map(|&value| value.into()).collect(),
+            ));
+            operations.push(Operation::new("Do", vec![Object::Name(name.into_bytes())]));
+            match self.annotations {
+                AnnotationMode::Keep => annots.extend(place_annotations(
+                    &mut self.doc,
+                    page_id,
+                    sheet_id,
+                    matrix,
+                    slot.clip,
+                )),
+                AnnotationMode::Flatten => {
+                    // Drawn in page space, within the placed page
+                    let appearances = annotation_appearances(&mut self.doc, page_id);
+                    for (number, (appearance_id, annot_matrix)) in
+                        appearances.into_iter().enumerate()
+                    {
+                        let name = format!("P{}A{}", index, number);
+                        xobjects.set(name.as_bytes(), Object::Reference(appearance_id));
+                        operations.push(Operation::new("q", vec![]));
+                        operations.push(Operation::new(
+                            "cm",
+                            annot_matrix.iter().map(|&value| value.into()).collect(),
+                        ));
+                        operations
+                            .push(Operation::new("Do", vec![Object::Name(name.into_bytes())]));
+                        operations.push(Operation::new("Q", vec![]));
+                    }
+                }
+                AnnotationMode::Drop => {}
+            }
+            operations.push(Operation::new("Q", vec![]));
+        }
+        operations.extend(marks_operations(&sheet.marks)?);
+
+        let content = Content { operations }.encode()?;
+        let content_id = self.doc.add_object(Stream::new(Dictionary::new(), content));
+
+        let mut resources = Dictionary::new();
+        resources.set(b"XObject", Object::Dictionary(xobjects));
+        if sheet
+            .marks
+            .iter()
+            .any(|mark| matches!(mark, Mark::Text { .. } | Mark::VerticalText { .. }))
+        {
+            let mut fonts = Dictionary::new();
+            fonts.set(MARK_FONT_NAME, Object::Dictionary(mark_font()));
+            resources.set(b"Font", Object::Dictionary(fonts));
+        }
+
+        let mut page_dict = Dictionary::new();
+        page_dict.set(b"Type", Object::Name(b"Page".to_vec()));
+        page_dict.set(
+            b"MediaBox",
+            Object::Array(vec![
+                Object::Real(0.0),
+                Object::Real(0.0),
+                Object::Real(sheet.width),
+                Object::Real(sheet.height),
+            ]),
+        );
+        page_dict.set(b"Resources", Object::Dictionary(resources));
+        page_dict.set(b"Contents", Object::Reference(content_id));
+        if !annots.is_empty() {
+            page_dict.set(b"Annots", Object::Array(annots));
+        }
+        if let Ok(pages_dict_id) = self
+            .doc
+            .catalog()
+            .and_then(|c| c.get(b"Pages"))
+            .and_then(|p| p.as_reference())
+        {
+            page_dict.set(b"Parent", Object::Reference(pages_dict_id));
+        }
+
+        self.doc
+            .objects
+            .insert(sheet_id, Object::Dictionary(page_dict));
+        Ok(sheet_id)
+    }
+
+    /// Generate new PDF made of composed sheets, filling the slots of each side in page order
+    fn generate_sheets_pdf(
+        &mut self,
+        new_order: Vec<u32>,
+        sides: &[SheetLayout],
+    ) -> Result<(), BookifyError> {
+        let pages_map: BTreeMap<u32, ObjectId> = self.doc.get_pages();
+        let mut new_kids_objects = Vec::new();
+        let mut moves = PageMoves::new();
+        let mut remaining_pages = &new_order[..];
+        for sheet in sides {
+            let (sheet_pages, rest) =
+                remaining_pages.split_at(sheet.slots.len().min(remaining_pages.len()));
+            remaining_pages = rest;
+            let mut placements = Vec::with_capacity(sheet_pages.len());
+            for (&page_num, &slot) in sheet_pages.iter().zip(&sheet.slots) {
+                if page_num == 0 {
+                    continue;
+                }
+                let &page_id = pages_map.get(&page_num).ok_or_else(|| {
+                    BookifyError::pdf_processing_failed(
+                        "Creating sheet",
+                        format!("Page {} not found in document", page_num),
+                    )
+                })?;
+                placements.push((page_id, slot));
+            }
+            let sheet_id = self.create_sheet_page(sheet, &placements)?;
+            new_kids_objects.push(Object::Reference(sheet_id));
+
+            // Destinations go to the first sheet showing their page
+            for &(page_id, slot) in &placements {
+                if let Entry::Vacant(entry) = moves.entry(page_id) {
+                    entry.insert(PageMove {
+                        page: sheet_id,
+                        matrix: Some(self.slot_matrix(page_id, &slot)?),
+                    });
+                }
+            }
+        }
+
+        let page_count = new_kids_objects.len() as u32;
+        self.update_document_pages(new_kids_objects, page_count)?;
+        self.validate_page_tree()?;
+        self.total_pages = page_count;
+        remap_page_references(&mut self.doc, &moves)?;
+        remove_page_labels(&mut self.doc)
+    }
+
+    /// Export booklet PDF. Returns the number of sheets of each signature.
+    pub fn export_booklet(&mut self, spec: &BookletSpec) -> Result<Vec<u32>, BookifyError> {
+        let BookletSpec {
+            layout,
+            binding,
+            method,
+            signature_size,
+            saddle_sheet_limit,
+            marks,
+            ref blank_position,
+        } = *spec;
+        if layout == LayoutType::MiniZine {
+            if binding == Binding::Top {
+                return Err(BookifyError::invalid_argument(
+                    "Top binding is not supported by the mini-zine layout",
+                ));
+            }
+            if method != BindingMethod::Saddle {
+                return Err(BookifyError::invalid_argument(
+                    "Mini-zine layout is folded from a single sheet, binding method must be saddle",
+                ));
+            }
+        }
+
+        // Pages only stack above and below the fold on composed sheets
+        if binding == Binding::Top && !marks && layout != LayoutType::MiniZine {
+            return Err(BookifyError::invalid_argument(
+                "Top binding stacks pages above and below the fold on composed sheets, use --marks",
+            ));
+        }
+
+        if signature_size.is_some()
+            && (method != BindingMethod::Saddle || layout == LayoutType::MiniZine)
+        {
+            return Err(BookifyError::invalid_argument(
+                "Signatures only apply to saddle-stitched two-up and four-up booklets",
+            ));
+        }
+
+        let signatures = match signature_size {
+            Some(SignatureSize::Sheets(size)) => split_signatures(self.total_pages, layout, size),
+            Some(SignatureSize::Auto) => {
+                balance_signatures(self.total_pages, layout, saddle_sheet_limit)
+            }
+            None => vec![sheet_count(self.total_pages, layout)],
+        };
+        // Impose whole sheets, then map the padded pages back to the document
+        let padded_pages = sheet_count(self.total_pages, layout) * layout.pages_per_sheet();
+        let padding = pad_pages(self.total_pages, padded_pages, blank_position);
+        let imposition = generate_bound_imposition(padded_pages, layout, method, &signatures)
+            .into_iter()
+            .map(|page| padding[page as usize - 1])
+            .collect();
+        let new_order = apply_binding(imposition, layout, binding);
+
+        // Turn pages on upside-down slots before they are placed
+        let pages_map: BTreeMap<u32, ObjectId> = self.doc.get_pages();
+        for (index, page_num) in new_order.iter().enumerate() {
+            if is_rotated_slot(index, layout, binding) {
+                if let Some(&page_id) = pages_map.get(page_num) {
+                    self.rotate_page(page_id, 180)?;
+                }
+            }
+        }
+
+        let source_pages = self.total_pages;
+        let (page_width, page_height) = self.page_size;
+        match layout {
+            LayoutType::MiniZine => {
+                let sheet = mini_zine_sheet(page_width, page_height);
+                let sides = vec![sheet; signatures.iter().sum::<u32>() as usize];
+                self.generate_sheets_pdf(new_order, &sides)?;
+            }
+            LayoutType::TwoUp | LayoutType::FourUp if marks => {
+                let sides = booklet_sides(layout, binding, page_width, page_height, &signatures);
+                self.generate_sheets_pdf(new_order, &sides)?;
+            }
+            LayoutType::TwoUp | LayoutType::FourUp => self.generate_new_pdf(new_order)?,
+        }
+        self.imposition = Some(Imposition {
+            layout: Some(layout),
+            binding: Some(binding),
+            method: Some(method),
+            signatures: signatures.clone(),
+            ..Imposition::new(
+                "booklet",
+                source_pages,
+                marks || layout == LayoutType::MiniZine,
+            )
+        });
+        Ok(signatures)
+    }
+
+    /// Export unbooklet PDF: every imposed sheet side cut into its slots, and the pieces put
+    /// back into reading order. `signatures` lists the sheets of each signature of a
+    /// saddle-stitched booklet, all sheets are taken as one signature when it is empty.
+    pub fn export_unbooklet(
+        &mut self,
+        layout: LayoutType,
+        binding: Binding,
+        method: BindingMethod,
+        signatures: &[u32],
+        margin: f32,
+    ) -> Result<(), BookifyError> {
+        if !self.total_pages.is_multiple_of(2) {
+            return Err(BookifyError::invalid_argument(format!(
+                "Imposed sheets have a front and a back, found an odd number of sides ({})",
+                self.total_pages
+            )));
+        }
+        let sheets: u32 = signatures.iter().sum();
+        if !signatures.is_empty() && 2 * sheets != self.total_pages {
+            return Err(BookifyError::invalid_argument(format!(
+                "Signatures of {} sheets do not match the {} imposed sides",
+                sheets, self.total_pages
+            )));
+        }
+
+        // Every side is taken to have the size of the first one
+        let pages_map: BTreeMap<u32, ObjectId> = self.doc.get_pages();
+        let first_page_id = *pages_map.values().next().ok_or_else(|| {
+            BookifyError::pdf_processing_failed("Getting page", "Document has no pages")
+        })?;
+        let (sheet_width, sheet_height) = self.displayed_page_size(first_page_id)?;
+
+        let pages_per_side = layout.pages_per_side();
+        let mut new_order = Vec::with_capacity(self.total_pages as usize * pages_per_side);
+        let mut pieces = Vec::with_capacity(new_order.capacity());
+        for index in generate_unbooklet_order(self.total_pages, layout, binding, method, signatures)
+        {
+            let index = index as usize - 1;
+            new_order.push((index / pages_per_side) as u32 + 1);
+            pieces.push(booklet_piece(
+                layout,
+                binding,
+                sheet_width,
+                sheet_height,
+                margin,
+                index % pages_per_side,
+                is_rotated_slot(index, layout, binding),
+            )?);
+        }
+        self.generate_sheets_pdf(new_order, &pieces)?;
+        self.page_size = Self::get_page_size(&self.doc)?;
+        self.imposition = None;
+        Ok(())
     }
 
     /// Export double-sided PDF
@@ -246,12 +1090,180 @@ impl PdfImposer {
         flip_type: FlipType,
         odd_even: OddEven,
     ) -> Result<(), BookifyError> {
+        let source_pages = self.total_pages;
         let new_order = generate_double_sided_order(self.total_pages, flip_type, odd_even);
-        self.generate_new_pdf(new_order)
+        self.generate_new_pdf(new_order)?;
+        self.imposition = Some(Imposition::new("double-sided", source_pages, false));
+        Ok(())
+    }
+
+    /// Export interleaved PDF: scanned fronts followed by the backs from another PDF,
+    /// put back into reading order
+    pub fn export_interleave(
+        &mut self,
+        backs_path: PathBuf,
+        flip_type: FlipType,
+    ) -> Result<(), BookifyError> {
+        let fronts = self.total_pages;
+        let backs = self.append_document(backs_path)?;
+        if backs != fronts && backs + 1 != fronts {
+            return Err(BookifyError::invalid_argument(format!(
+                "{} fronts need {} or {} backs, found {}",
+                fronts,
+                fronts,
+                fronts - 1,
+                backs
+            )));
+        }
+        self.generate_new_pdf(generate_interleave_order(fronts, backs, flip_type))?;
+        self.imposition = None;
+        Ok(())
+    }
+
+    /// Export step-and-repeat grid PDF
+    pub fn export_grid(
+        &mut self,
+        spec: &GridSpec,
+        mode: GridMode,
+        duplex: Duplex,
+    ) -> Result<(), BookifyError> {
+        let sheet = grid_sheet(spec, self.page_size.0, self.page_size.1)?;
+
+        // Long edge of a portrait sheet is vertical
+        let portrait = sheet.width <= sheet.height;
+        let back_flip = match duplex {
+            Duplex::None => None,
+            Duplex::LongEdge if portrait => Some(FlipAxis::Vertical),
+            Duplex::ShortEdge if !portrait => Some(FlipAxis::Vertical),
+            Duplex::LongEdge | Duplex::ShortEdge => Some(FlipAxis::Horizontal),
+        };
+
+        let source_pages = self.total_pages;
+        let new_order =
+            generate_grid_order(self.total_pages, spec.columns, spec.rows, mode, back_flip);
+        let sides = vec![sheet; new_order.len() / (spec.columns * spec.rows) as usize];
+        self.generate_sheets_pdf(new_order, &sides)?;
+        self.imposition = Some(Imposition::new("grid", source_pages, true));
+        Ok(())
+    }
+
+    /// Export flashcards: questions of this document on fronts, answers of another on backs
+    pub fn export_flashcards(
+        &mut self,
+        answers_path: PathBuf,
+        spec: &GridSpec,
+        duplex: Duplex,
+    ) -> Result<(), BookifyError> {
+        if duplex == Duplex::None {
+            return Err(BookifyError::invalid_argument(
+                "Flashcards are printed on both sides, duplex must be long-edge or short-edge",
+            ));
+        }
+
+        let questions = self.total_pages;
+        let answers = self.append_document(answers_path)?;
+        self.generate_new_pdf(generate_flashcard_order(questions, answers))?;
+        self.export_grid(spec, GridMode::Sequence, duplex)?;
+        self.imposition = Some(Imposition::new("flashcards", questions + answers, true));
+        Ok(())
+    }
+
+    /// Export poster PDF: every page enlarged and tiled across several sheets
+    pub fn export_poster(&mut self, spec: &PosterSpec) -> Result<(), BookifyError> {
+        let pages_map: BTreeMap<u32, ObjectId> = self.doc.get_pages();
+        let mut new_order = Vec::new();
+        let mut sides = Vec::new();
+        for (&page_num, &page_id) in &pages_map {
+            let (width, height) = self.displayed_page_size(page_id)?;
+            let sheets = poster_sheets(spec, width, height)?;
+            new_order.extend(vec![page_num; sheets.len()]);
+            sides.extend(sheets);
+        }
+        let source_pages = self.total_pages;
+        self.generate_sheets_pdf(new_order, &sides)?;
+        self.imposition = Some(Imposition::new("poster", source_pages, true));
+        Ok(())
+    }
+
+    /// Export brochure PDF: panels placed on the outside and inside of folded sheets
+    pub fn export_brochure(
+        &mut self,
+        fold: FoldType,
+        sheet: PaperSize,
+        tuck: f32,
+        fold_marks: bool,
+    ) -> Result<(), BookifyError> {
+        let sides = brochure_sides(fold, sheet.width, sheet.height, tuck, fold_marks)?;
+        let new_order = generate_brochure_order(self.total_pages, fold);
+        let sides: Vec<SheetLayout> = sides
+            .iter()
+            .cycle()
+            .take(new_order.len() / fold.panels_per_side())
+            .cloned()
+            .collect();
+        let source_pages = self.total_pages;
+        self.generate_sheets_pdf(new_order, &sides)?;
+        self.imposition = Some(Imposition::new("brochure", source_pages, true));
+        Ok(())
+    }
+
+    /// Export handout PDF: slides in reading order with headers, numbers and optional notes
+    pub fn export_handout(&mut self, spec: &HandoutSpec) -> Result<(), BookifyError> {
+        let pages_map: BTreeMap<u32, ObjectId> = self.doc.get_pages();
+        let &first_page_id = pages_map
+            .values()
+            .next()
+            .ok_or_else(|| BookifyError::invalid_pdf_format("Document has no pages"))?;
+        let (slide_width, slide_height) = self.displayed_page_size(first_page_id)?;
+
+        let sides = handout_sides(spec, slide_width, slide_height, self.total_pages)?;
+        let source_pages = self.total_pages;
+        let new_order = (1..=source_pages).collect();
+        self.generate_sheets_pdf(new_order, &sides)?;
+        self.imposition = Some(Imposition::new("handout", source_pages, true));
+        Ok(())
+    }
+
+    /// Export wrap-around cover PDF from art pages: front cover, back cover and optional spine
+    pub fn export_cover_spread(&mut self, spec: &CoverSpec) -> Result<(), BookifyError> {
+        let new_order = match self.total_pages {
+            2 => vec![1, 2, 0],
+            3 => vec![1, 2, 3],
+            pages => {
+                return Err(BookifyError::invalid_argument(format!(
+                    "Cover art needs 2 or 3 pages (front, back, spine), got {}",
+                    pages
+                )))
+            }
+        };
+        let source_pages = self.total_pages;
+        let spread = cover_spread(spec)?;
+        self.generate_sheets_pdf(new_order, &[spread])?;
+        self.imposition = Some(Imposition::new("cover", source_pages, true));
+        Ok(())
     }
 
     /// Save document to specified path
     pub fn save(&mut self, output_path: PathBuf) -> Result<(), BookifyError> {
+        write_imposition(&mut self.doc, self.imposition.as_ref())?;
+        let created = if self.reproducible {
+            reproducible_timestamp()?
+        } else {
+            Utc::now()
+        };
+        let provenance = Provenance {
+            sources: &self.sources,
+            job: &self.job,
+            imposition: self.imposition.as_ref(),
+            created,
+        };
+        write_provenance(&mut self.doc, &provenance)?;
+        // Pages dropped from the page tree, and their resources, are no longer referenced
+        self.doc.prune_objects();
+        if self.reproducible {
+            self.doc.renumber_objects();
+            write_content_id(&mut self.doc)?;
+        }
         self.doc
             .save(&output_path)
             .map_err(|e| BookifyError::io_error(e, &output_path))?;
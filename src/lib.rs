@@ -1,4 +1,9 @@
+pub mod annotations;
 pub mod args;
 pub mod calc;
 pub mod error;
 pub mod imposition;
+pub mod marks;
+pub mod metadata;
+pub mod navigation;
+pub mod sheet;
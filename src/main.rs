@@ -1,25 +1,77 @@
 use bookify_rs::{
-    args::{BaseOptions, BookletOptions, Cli, Commands, DoubleSidedOptions},
+    args::{
+        AnnotationMode, BaseOptions, Binding, BindingMethod, BookletOptions, BrochureOptions, Cli,
+        Commands, CoverOptions, DoubleSidedOptions, ExtractOptions, FlashcardOptions, GridOptions,
+        HandoutOptions, ImposeOptions, InsertBlankOptions, InterleaveOptions, LayoutType,
+        PosterOptions, RotateOptions, SignatureSize, SplitOptions, UnbookletOptions, POINTS_PER_MM,
+    },
+    calc::{document_start_blanks, resolve_page_index},
     error::BookifyError,
-    imposition::PdfImposer,
+    imposition::{BookletSpec, PdfImposer},
+    marks::win_ansi_encode,
+    metadata::Job,
+    sheet::{spine_width, Caliper, CoverSpec, GridSpec, HandoutSpec, PosterSpec, SLUG_WIDTH},
 };
-use clap::Parser;
+use clap::{ArgMatches, CommandFactory, FromArgMatches};
+use std::fs;
 use std::path::{Path, PathBuf};
 use std::process;
 use tempfile::Builder;
 
 fn main() {
-    let args = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let args = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let job = job_from_matches(&matches);
 
     if let Err(e) = match args.command {
-        Commands::Booklet(opts) => handle_booklet(opts),
-        Commands::DoubleSided(opts) => handle_double_sided(opts),
+        Commands::Booklet(opts) => handle_booklet(opts, &job),
+        Commands::Unbooklet(opts) => handle_unbooklet(opts, &job),
+        Commands::DoubleSided(opts) => handle_double_sided(opts, &job),
+        Commands::Interleave(opts) => handle_interleave(opts, &job),
+        Commands::Grid(opts) => handle_grid(opts, &job),
+        Commands::Flashcards(opts) => handle_flashcards(opts, &job),
+        Commands::Poster(opts) => handle_poster(opts, &job),
+        Commands::Brochure(opts) => handle_brochure(opts, &job),
+        Commands::Handout(opts) => handle_handout(opts, &job),
+        Commands::Cover(opts) => handle_cover(opts, &job),
+        Commands::Extract(opts) => handle_extract(opts, &job),
+        Commands::Split(opts) => handle_split(opts, &job),
+        Commands::Rotate(opts) => handle_rotate(opts, &job),
+        Commands::InsertBlank(opts) => handle_insert_blank(opts, &job),
     } {
         eprintln!("Error: {}", e);
         process::exit(1);
     }
 }
 
+/// Job recorded in outputs: the subcommand and the value of every option, defaults included
+fn job_from_matches(matches: &ArgMatches) -> Job {
+    let Some((command, sub_matches)) = matches.subcommand() else {
+        return Job::default();
+    };
+    let cli = Cli::command();
+    let Some(subcommand) = cli.find_subcommand(command) else {
+        return Job::default();
+    };
+    // Input files are recorded as sources, output paths are not part of the job
+    let parameters = subcommand
+        .get_arguments()
+        .map(|arg| arg.get_id().as_str())
+        .filter(|&id| !matches!(id, "input" | "input_list" | "output" | "temp"))
+        .filter_map(|id| {
+            let values: Vec<String> = sub_matches
+                .get_raw(id)?
+                .map(|value| value.to_string_lossy().into_owned())
+                .collect();
+            Some((id.replace('_', "-"), values.join(",")))
+        })
+        .collect();
+    Job {
+        command: command.to_string(),
+        parameters,
+    }
+}
+
 /// Generate output path with opts
 fn handle_output_path(
     base_opts: &BaseOptions,
@@ -43,6 +95,65 @@ fn handle_output_path(
     Ok(output_path)
 }
 
+/// Input files in order: positional inputs, then the entries of the list file
+fn input_files(base_opts: &BaseOptions) -> Result<Vec<PathBuf>, BookifyError> {
+    let mut files = base_opts.input.clone();
+    if let Some(list_path) = &base_opts.input_list {
+        let list = fs::read_to_string(list_path)
+            .map_err(|e| BookifyError::io_error(e, list_path.clone()))?;
+        let list_dir = list_path.parent().unwrap_or_else(|| Path::new(""));
+        files.extend(
+            list.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(|line| list_dir.join(line)),
+        );
+    }
+    if files.is_empty() {
+        return Err(BookifyError::invalid_argument("No input files"));
+    }
+    Ok(files)
+}
+
+/// Open the input PDFs, concatenated
+fn merge_inputs(base_opts: &BaseOptions, job: &Job) -> Result<PdfImposer, BookifyError> {
+    let files = input_files(base_opts)?;
+    let mut imposer = PdfImposer::new(files[0].clone())?;
+    for file in &files[1..] {
+        imposer.append_blank_pages(document_start_blanks(
+            imposer.total_pages(),
+            base_opts.document_start,
+        ))?;
+        imposer.append_document(file.clone())?;
+    }
+    imposer.set_job(job.clone());
+    imposer.set_reproducible(base_opts.reproducible);
+    Ok(imposer)
+}
+
+/// Open the input PDFs, concatenated, keeping only the selected pages
+fn open_input(base_opts: &BaseOptions, job: &Job) -> Result<PdfImposer, BookifyError> {
+    let mut imposer = merge_inputs(base_opts, job)?;
+    if let Some(selection) = &base_opts.pages {
+        imposer.select_pages(selection)?;
+    }
+    Ok(imposer)
+}
+
+/// Open the input PDFs like `open_input`, refusing an input that is already imposed
+/// unless forced
+fn open_unimposed_input(
+    base_opts: &BaseOptions,
+    impose_opts: &ImposeOptions,
+    job: &Job,
+) -> Result<PdfImposer, BookifyError> {
+    let imposer = open_input(base_opts, job)?;
+    if !impose_opts.force {
+        imposer.ensure_not_imposed()?;
+    }
+    Ok(imposer)
+}
+
 /// Conditional print result
 fn print_output_result(temp: bool, output_path: &Path, message: &str) {
     if temp {
@@ -53,13 +164,114 @@ fn print_output_result(temp: bool, output_path: &Path, message: &str) {
 }
 
 /// Handle booklet imposition command
-fn handle_booklet(opts: BookletOptions) -> Result<(), BookifyError> {
-    let input_path = opts.base.input.clone();
+fn handle_booklet(opts: BookletOptions, job: &Job) -> Result<(), BookifyError> {
+    let input_path = opts.base.primary_input();
     let prefix = format!("booklet-{:?}", opts.layout);
     let output_path = handle_output_path(&opts.base, &input_path, &prefix)?;
 
-    let mut imposer = PdfImposer::new(input_path)?;
-    imposer.export_booklet(opts.layout)?;
+    let mut imposer = open_unimposed_input(&opts.base, &opts.impose, job)?;
+    imposer.set_annotations(opts.compose.annotations);
+    // Booklet pages are only reordered unless they are composed onto sheets
+    if opts.compose.annotations != AnnotationMode::Keep
+        && !opts.marks
+        && opts.layout != LayoutType::MiniZine
+    {
+        eprintln!(
+            "Warning: --annotations only applies to booklets composed with --marks, pages keep their annotations"
+        );
+    }
+    let binding = opts
+        .binding
+        .or_else(|| imposer.document_binding())
+        .unwrap_or(Binding::Ltr);
+
+    let mut cover_pages = Vec::new();
+    let cover = match (opts.cover, &opts.cover_pages) {
+        (Some(cover_path), _) => {
+            let mut cover = PdfImposer::new(cover_path)?;
+            cover.set_job(job.clone());
+            cover.set_reproducible(opts.base.reproducible);
+            cover.set_annotations(opts.compose.annotations);
+            Some(cover)
+        }
+        (None, Some(indices)) => {
+            let total_pages = imposer.total_pages();
+            let pages = indices
+                .iter()
+                .map(|&index| {
+                    resolve_page_index(index, total_pages).ok_or_else(|| {
+                        BookifyError::invalid_argument(format!(
+                            "Cover page {} is outside the {}-page document",
+                            index, total_pages
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<u32>, BookifyError>>()?;
+            let cover = imposer.split_off_pages(&pages)?;
+            cover_pages = pages;
+            Some(cover)
+        }
+        (None, None) => None,
+    };
+    if let Some(cover) = &cover {
+        if opts.layout == LayoutType::MiniZine {
+            return Err(BookifyError::invalid_argument(
+                "Mini-zine layout is folded from a single sheet and has no separate cover",
+            ));
+        }
+        if cover.total_pages() != 4 {
+            return Err(BookifyError::invalid_argument(format!(
+                "Cover needs 4 pages, got {}",
+                cover.total_pages()
+            )));
+        }
+    }
+
+    if opts.recto_chapters || opts.chapter_pages.is_some() {
+        let chapter_starts = match &opts.chapter_pages {
+            // Renumber the given pages once the cover pages are out of the body
+            Some(pages) => pages
+                .iter()
+                .filter(|page| !cover_pages.contains(page))
+                .map(|&page| {
+                    page - cover_pages.iter().filter(|&&cover| cover < page).count() as u32
+                })
+                .collect(),
+            None => imposer.outline_chapter_starts(),
+        };
+        if chapter_starts.is_empty() {
+            eprintln!("Warning: no chapters found, the document has no outline");
+        }
+        let blanks = imposer.pad_chapters_to_recto(&chapter_starts)?;
+        eprintln!(
+            "Added {} blank pages so chapters start on right-hand pages",
+            blanks
+        );
+    }
+
+    let spec = BookletSpec {
+        layout: opts.layout,
+        binding,
+        method: opts.binding_method,
+        signature_size: opts.signature_size,
+        saddle_sheet_limit: opts.saddle_sheet_limit,
+        marks: opts.marks,
+        blank_position: opts.blank_position.clone(),
+    };
+    let signatures = imposer.export_booklet(&spec)?;
+    if opts.signature_size == Some(SignatureSize::Auto) {
+        let sizes: Vec<String> = signatures.iter().map(u32::to_string).collect();
+        eprintln!("Signatures: {} sheets", sizes.join("+"));
+    }
+    if opts.binding_method == BindingMethod::Saddle && opts.layout != LayoutType::MiniZine {
+        let sheets = signatures.iter().copied().max().unwrap_or(0);
+        if sheets > opts.saddle_sheet_limit {
+            eprintln!(
+                "Warning: saddle-stitched signature needs {} sheets, more than the limit of {}, consider --signature-size or --binding-method perfect",
+                sheets, opts.saddle_sheet_limit
+            );
+        }
+    }
     imposer.save(output_path.clone())?;
 
     print_output_result(
@@ -70,16 +282,90 @@ fn handle_booklet(opts: BookletOptions) -> Result<(), BookifyError> {
             output_path.display()
         ),
     );
+
+    if let Some(mut cover) = cover {
+        // Cover wraps the whole book with a single fold
+        let cover_path = output_path.with_extension("cover.pdf");
+        cover.export_booklet(&BookletSpec {
+            marks: opts.marks,
+            ..BookletSpec::new(LayoutType::TwoUp, binding)
+        })?;
+        cover.save(cover_path.clone())?;
+
+        print_output_result(
+            opts.base.temp,
+            &cover_path,
+            &format!(
+                "Cover imposition completed, output file: {}",
+                cover_path.display()
+            ),
+        );
+    }
+    Ok(())
+}
+
+/// Handle unbooklet command
+fn handle_unbooklet(opts: UnbookletOptions, job: &Job) -> Result<(), BookifyError> {
+    let mut imposer = open_input(&opts.base, job)?;
+    imposer.set_annotations(opts.compose.annotations);
+
+    // Sheets imposed by bookify-rs record how they were imposed
+    let recorded = imposer
+        .imposition()
+        .filter(|imposition| imposition.kind == "booklet" && imposition.sheets)
+        .cloned();
+    let layout = opts
+        .layout
+        .or_else(|| recorded.as_ref().and_then(|imposition| imposition.layout))
+        .unwrap_or(LayoutType::FourUp);
+    let binding = opts
+        .binding
+        .or_else(|| recorded.as_ref().and_then(|imposition| imposition.binding))
+        .or_else(|| imposer.document_binding())
+        .unwrap_or(Binding::Ltr);
+    let method = recorded
+        .as_ref()
+        .and_then(|imposition| imposition.method)
+        .unwrap_or(BindingMethod::Saddle);
+    let signatures = recorded
+        .as_ref()
+        .map(|imposition| imposition.signatures.clone())
+        .unwrap_or_default();
+    let margin = match (opts.margin, &recorded) {
+        (Some(margin), _) => margin * POINTS_PER_MM,
+        (None, Some(_)) => SLUG_WIDTH,
+        (None, None) => 0.0,
+    };
+
+    let input_path = opts.base.primary_input();
+    let prefix = format!("unbooklet-{:?}", layout);
+    let output_path = handle_output_path(&opts.base, &input_path, &prefix)?;
+
+    imposer.export_unbooklet(layout, binding, method, &signatures, margin)?;
+    imposer.save(output_path.clone())?;
+
+    print_output_result(
+        opts.base.temp,
+        &output_path,
+        &format!(
+            "Unbooklet completed, {} pages, output file: {}",
+            imposer.total_pages(),
+            output_path.display()
+        ),
+    );
     Ok(())
 }
 
 /// Handle double-sided printing command
-fn handle_double_sided(opts: DoubleSidedOptions) -> Result<(), BookifyError> {
-    let input_path = opts.base.input.clone();
+fn handle_double_sided(opts: DoubleSidedOptions, job: &Job) -> Result<(), BookifyError> {
+    let input_path = opts.base.primary_input();
     let prefix = format!("double-sided-{:?}-{:?}", opts.flip_type, opts.odd_even);
     let output_path = handle_output_path(&opts.base, &input_path, &prefix)?;
 
-    let mut imposer = PdfImposer::new(input_path)?;
+    let mut imposer = open_input(&opts.base, job)?;
+    if !opts.impose.force {
+        imposer.ensure_not_double_sided()?;
+    }
     imposer.export_double_sided(opts.flip_type, opts.odd_even)?;
     imposer.save(output_path.clone())?;
 
@@ -94,3 +380,321 @@ fn handle_double_sided(opts: DoubleSidedOptions) -> Result<(), BookifyError> {
     );
     Ok(())
 }
+
+/// Handle interleave command
+fn handle_interleave(opts: InterleaveOptions, job: &Job) -> Result<(), BookifyError> {
+    let input_path = opts.base.primary_input();
+    let prefix = format!("interleave-{:?}", opts.flip_type);
+    let output_path = handle_output_path(&opts.base, &input_path, &prefix)?;
+
+    let mut imposer = open_input(&opts.base, job)?;
+    imposer.export_interleave(opts.backs, opts.flip_type)?;
+    imposer.save(output_path.clone())?;
+
+    print_output_result(
+        opts.base.temp,
+        &output_path,
+        &format!(
+            "Interleave completed, {} pages, output file: {}",
+            imposer.total_pages(),
+            output_path.display()
+        ),
+    );
+    Ok(())
+}
+
+/// Handle step-and-repeat grid command
+fn handle_grid(opts: GridOptions, job: &Job) -> Result<(), BookifyError> {
+    let input_path = opts.base.primary_input();
+    let prefix = format!("grid-{}x{}-{:?}", opts.columns, opts.rows, opts.mode);
+    let output_path = handle_output_path(&opts.base, &input_path, &prefix)?;
+
+    let spec = GridSpec {
+        columns: opts.columns,
+        rows: opts.rows,
+        gutter: opts.gutter * POINTS_PER_MM,
+        margin: opts.margin * POINTS_PER_MM,
+        sheet: opts.sheet,
+        cut_marks: opts.cut_marks,
+    };
+
+    let mut imposer = open_unimposed_input(&opts.base, &opts.impose, job)?;
+    imposer.set_annotations(opts.compose.annotations);
+    imposer.export_grid(&spec, opts.mode, opts.duplex)?;
+    imposer.save(output_path.clone())?;
+
+    print_output_result(
+        opts.base.temp,
+        &output_path,
+        &format!(
+            "Grid imposition completed, output file: {}",
+            output_path.display()
+        ),
+    );
+    Ok(())
+}
+
+/// Handle flashcards command
+fn handle_flashcards(opts: FlashcardOptions, job: &Job) -> Result<(), BookifyError> {
+    let input_path = opts.base.primary_input();
+    let prefix = format!("flashcards-{}x{}", opts.columns, opts.rows);
+    let output_path = handle_output_path(&opts.base, &input_path, &prefix)?;
+
+    let spec = GridSpec {
+        columns: opts.columns,
+        rows: opts.rows,
+        gutter: opts.gutter * POINTS_PER_MM,
+        margin: opts.margin * POINTS_PER_MM,
+        sheet: opts.sheet,
+        cut_marks: opts.cut_marks,
+    };
+
+    let mut imposer = open_unimposed_input(&opts.base, &opts.impose, job)?;
+    imposer.set_annotations(opts.compose.annotations);
+    imposer.export_flashcards(opts.answers, &spec, opts.duplex)?;
+    imposer.save(output_path.clone())?;
+
+    print_output_result(
+        opts.base.temp,
+        &output_path,
+        &format!(
+            "Flashcard imposition completed, output file: {}",
+            output_path.display()
+        ),
+    );
+    Ok(())
+}
+
+/// Handle poster command
+fn handle_poster(opts: PosterOptions, job: &Job) -> Result<(), BookifyError> {
+    let input_path = opts.base.primary_input();
+    let prefix = format!("poster-{}x", opts.scale);
+    let output_path = handle_output_path(&opts.base, &input_path, &prefix)?;
+
+    let spec = PosterSpec {
+        sheet: opts.sheet,
+        margin: opts.margin * POINTS_PER_MM,
+        overlap: opts.overlap * POINTS_PER_MM,
+        scale: opts.scale,
+        overview: !opts.no_overview,
+    };
+
+    let mut imposer = open_unimposed_input(&opts.base, &opts.impose, job)?;
+    imposer.set_annotations(opts.compose.annotations);
+    imposer.export_poster(&spec)?;
+    imposer.save(output_path.clone())?;
+
+    print_output_result(
+        opts.base.temp,
+        &output_path,
+        &format!(
+            "Poster tiling completed, output file: {}",
+            output_path.display()
+        ),
+    );
+    Ok(())
+}
+
+/// Handle brochure command
+fn handle_brochure(opts: BrochureOptions, job: &Job) -> Result<(), BookifyError> {
+    let input_path = opts.base.primary_input();
+    let prefix = format!("brochure-{:?}", opts.fold);
+    let output_path = handle_output_path(&opts.base, &input_path, &prefix)?;
+
+    let mut imposer = open_unimposed_input(&opts.base, &opts.impose, job)?;
+    imposer.set_annotations(opts.compose.annotations);
+    imposer.export_brochure(
+        opts.fold,
+        opts.sheet,
+        opts.tuck * POINTS_PER_MM,
+        opts.fold_marks,
+    )?;
+    imposer.save(output_path.clone())?;
+
+    print_output_result(
+        opts.base.temp,
+        &output_path,
+        &format!(
+            "Brochure imposition completed, output file: {}",
+            output_path.display()
+        ),
+    );
+    Ok(())
+}
+
+/// Handle handout command
+fn handle_handout(opts: HandoutOptions, job: &Job) -> Result<(), BookifyError> {
+    let input_path = opts.base.primary_input();
+    let prefix = format!("handout-{}", opts.slides);
+    let output_path = handle_output_path(&opts.base, &input_path, &prefix)?;
+
+    // File names the mark font cannot print, such as Chinese ones, leave only the page number
+    let header = opts.header.unwrap_or_else(|| {
+        input_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .filter(|stem| win_ansi_encode(stem).is_ok())
+            .unwrap_or_default()
+    });
+    let spec = HandoutSpec {
+        slides: opts.slides,
+        sheet: opts.sheet,
+        margin: opts.margin * POINTS_PER_MM,
+        notes: opts.notes,
+        header,
+    };
+
+    let mut imposer = open_unimposed_input(&opts.base, &opts.impose, job)?;
+    imposer.set_annotations(opts.compose.annotations);
+    imposer.export_handout(&spec)?;
+    imposer.save(output_path.clone())?;
+
+    print_output_result(
+        opts.base.temp,
+        &output_path,
+        &format!("Handout completed, output file: {}", output_path.display()),
+    );
+    Ok(())
+}
+
+/// Handle wrap-around cover command
+fn handle_cover(opts: CoverOptions, job: &Job) -> Result<(), BookifyError> {
+    let input_path = opts.base.primary_input();
+    let output_path = handle_output_path(&opts.base, &input_path, "cover")?;
+
+    let body = open_unimposed_input(&opts.base, &opts.impose, job)?;
+    let caliper = match (opts.ppi, opts.caliper) {
+        (_, Some(caliper)) => Caliper::SheetThickness(caliper * POINTS_PER_MM),
+        (Some(ppi), None) => Caliper::PagesPerInch(ppi),
+        (None, None) => {
+            return Err(BookifyError::invalid_argument(
+                "Paper thickness is needed, use --ppi or --caliper",
+            ))
+        }
+    };
+    let (page_width, page_height) = body.page_size();
+    let spec = CoverSpec {
+        page_width,
+        page_height,
+        spine_width: spine_width(body.total_pages(), caliper),
+        binding: opts
+            .binding
+            .or_else(|| body.document_binding())
+            .unwrap_or(Binding::Ltr),
+        spine_text: opts.spine_text,
+        marks: opts.marks,
+    };
+
+    let mut imposer = PdfImposer::new(opts.art)?;
+    imposer.set_job(job.clone());
+    imposer.set_reproducible(opts.base.reproducible);
+    imposer.set_annotations(opts.compose.annotations);
+    imposer.export_cover_spread(&spec)?;
+    imposer.save(output_path.clone())?;
+
+    print_output_result(
+        opts.base.temp,
+        &output_path,
+        &format!(
+            "Cover spread completed, spine width {:.2}mm, output file: {}",
+            spec.spine_width / POINTS_PER_MM,
+            output_path.display()
+        ),
+    );
+    Ok(())
+}
+
+/// Handle page extraction command
+fn handle_extract(opts: ExtractOptions, job: &Job) -> Result<(), BookifyError> {
+    if opts.base.pages.is_none() {
+        return Err(BookifyError::invalid_argument(
+            "Select the pages to extract with --pages",
+        ));
+    }
+    let input_path = opts.base.primary_input();
+    let output_path = handle_output_path(&opts.base, &input_path, "extract")?;
+
+    let mut imposer = open_input(&opts.base, job)?;
+    imposer.save(output_path.clone())?;
+
+    print_output_result(
+        opts.base.temp,
+        &output_path,
+        &format!(
+            "Extracted {} pages, output file: {}",
+            imposer.total_pages(),
+            output_path.display()
+        ),
+    );
+    Ok(())
+}
+
+/// Handle document splitting command
+fn handle_split(opts: SplitOptions, job: &Job) -> Result<(), BookifyError> {
+    let input_path = opts.base.primary_input();
+    let output_path = handle_output_path(&opts.base, &input_path, "split")?;
+
+    let parts = open_input(&opts.base, job)?.split_every(opts.every)?;
+    for (index, part) in parts.enumerate() {
+        let mut part = part?;
+        let part_path = output_path.with_extension(format!("{}.pdf", index + 1));
+        part.save(part_path.clone())?;
+
+        print_output_result(
+            opts.base.temp,
+            &part_path,
+            &format!(
+                "Part {} with {} pages, output file: {}",
+                index + 1,
+                part.total_pages(),
+                part_path.display()
+            ),
+        );
+    }
+    Ok(())
+}
+
+/// Handle page rotation command
+fn handle_rotate(opts: RotateOptions, job: &Job) -> Result<(), BookifyError> {
+    let input_path = opts.base.primary_input();
+    let output_path = handle_output_path(&opts.base, &input_path, "rotate")?;
+
+    // --pages selects the pages to rotate instead of the pages to keep
+    let mut imposer = merge_inputs(&opts.base, job)?;
+    let pages = match &opts.base.pages {
+        Some(selection) => selection
+            .resolve(imposer.total_pages())
+            .map_err(BookifyError::invalid_argument)?,
+        None => (1..=imposer.total_pages()).collect(),
+    };
+    imposer.rotate_pages(&pages, opts.angle)?;
+    imposer.save(output_path.clone())?;
+
+    print_output_result(
+        opts.base.temp,
+        &output_path,
+        &format!("Rotation completed, output file: {}", output_path.display()),
+    );
+    Ok(())
+}
+
+/// Handle blank page insertion command
+fn handle_insert_blank(opts: InsertBlankOptions, job: &Job) -> Result<(), BookifyError> {
+    let input_path = opts.base.primary_input();
+    let output_path = handle_output_path(&opts.base, &input_path, "insert-blank")?;
+
+    let mut imposer = open_input(&opts.base, job)?;
+    imposer.insert_blank_pages(&opts.after)?;
+    imposer.save(output_path.clone())?;
+
+    print_output_result(
+        opts.base.temp,
+        &output_path,
+        &format!(
+            "Inserted {} blank pages, output file: {}",
+            opts.after.len(),
+            output_path.display()
+        ),
+    );
+    Ok(())
+}
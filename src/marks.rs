@@ -0,0 +1,470 @@
+use lopdf::{content::Operation, Dictionary, Object, StringFormat};
+
+use crate::error::BookifyError;
+
+/// Length of the ticks drawn at both ends of a fold line, in points
+pub const FOLD_MARK_LENGTH: f32 = 12.0;
+
+/// Length of crop mark ticks, in points
+pub const CROP_MARK_LENGTH: f32 = 12.0;
+
+/// Distance between the trim edge and the start of a crop mark, in points
+pub const CROP_MARK_OFFSET: f32 = 3.0;
+
+/// Length of a collation mark along the spine, in points
+pub const COLLATION_MARK_LENGTH: f32 = 14.0;
+
+/// Thickness of a collation mark across the spine, in points
+pub const COLLATION_MARK_WIDTH: f32 = 6.0;
+
+/// Font size of slug text, in points
+pub const SLUG_TEXT_SIZE: f32 = 6.0;
+
+/// Resource name of the font used by text marks
+pub const MARK_FONT_NAME: &str = "BookifyMark";
+
+/// Width of mark lines, in points
+const MARK_LINE_WIDTH: f32 = 0.5;
+
+/// WinAnsiEncoding codes 0x80 to 0x9F, the rest of its upper half matches Latin-1
+const WIN_ANSI_HIGH_CODES: [(char, u8); 27] = [
+    ('€', 0x80),
+    ('‚', 0x82),
+    ('ƒ', 0x83),
+    ('„', 0x84),
+    ('…', 0x85),
+    ('†', 0x86),
+    ('‡', 0x87),
+    ('ˆ', 0x88),
+    ('‰', 0x89),
+    ('Š', 0x8A),
+    ('‹', 0x8B),
+    ('Œ', 0x8C),
+    ('Ž', 0x8E),
+    ('‘', 0x91),
+    ('’', 0x92),
+    ('“', 0x93),
+    ('”', 0x94),
+    ('•', 0x95),
+    ('–', 0x96),
+    ('—', 0x97),
+    ('˜', 0x98),
+    ('™', 0x99),
+    ('š', 0x9A),
+    ('›', 0x9B),
+    ('œ', 0x9C),
+    ('ž', 0x9E),
+    ('Ÿ', 0x9F),
+];
+
+/// Printer's mark drawn on a sheet, coordinates in PDF points
+#[derive(Clone, Debug, PartialEq)]
+pub enum Mark {
+    /// Cut line, drawn solid along its whole length
+    Cut { from: (f32, f32), to: (f32, f32) },
+    /// Fold line, drawn as dashed ticks at both ends so it stays invisible on the pages
+    Fold { from: (f32, f32), to: (f32, f32) },
+    /// Plain line printed as part of the sheet, used for frames and ruled notes
+    Line { from: (f32, f32), to: (f32, f32) },
+    /// Filled black rectangle, used for collation marks
+    Bar {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    },
+    /// Single line of text, used for slug information
+    Text { at: (f32, f32), text: String },
+    /// Single line of text running top to bottom, used for spine titles
+    VerticalText {
+        at: (f32, f32),
+        size: f32,
+        text: String,
+    },
+}
+
+impl Mark {
+    /// Line segments to stroke for this mark
+    fn segments(&self) -> Vec<((f32, f32), (f32, f32))> {
+        match *self {
+            Mark::Cut { from, to } | Mark::Line { from, to } => vec![(from, to)],
+            Mark::Fold { from, to } => {
+                let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+                let length = (dx * dx + dy * dy).sqrt();
+                if length <= FOLD_MARK_LENGTH * 2.0 {
+                    return vec![(from, to)];
+                }
+                let (ux, uy) = (
+                    dx / length * FOLD_MARK_LENGTH,
+                    dy / length * FOLD_MARK_LENGTH,
+                );
+                vec![
+                    (from, (from.0 + ux, from.1 + uy)),
+                    ((to.0 - ux, to.1 - uy), to),
+                ]
+            }
+            Mark::Bar { .. } | Mark::Text { .. } | Mark::VerticalText { .. } => vec![],
+        }
+    }
+}
+
+/// Builds crop marks around a trim box and along the internal cut lines.
+///
+/// Ticks are drawn in the slug, outside the trim box, so they disappear once the sheet is cut.
+///
+/// # Parameters
+/// * `trim` - Trim box `[x0, y0, x1, y1]`
+/// * `cuts_x` - Vertical cut positions inside the trim box
+/// * `cuts_y` - Horizontal cut positions inside the trim box
+pub fn crop_marks(trim: [f32; 4], cuts_x: &[f32], cuts_y: &[f32]) -> Vec<Mark> {
+    let [x0, y0, x1, y1] = trim;
+    let near = CROP_MARK_OFFSET;
+    let far = CROP_MARK_OFFSET + CROP_MARK_LENGTH;
+
+    let mut marks = Vec::new();
+    for &x in [x0].iter().chain(cuts_x).chain([x1].iter()) {
+        marks.push(Mark::Cut {
+            from: (x, y0 - far),
+            to: (x, y0 - near),
+        });
+        marks.push(Mark::Cut {
+            from: (x, y1 + near),
+            to: (x, y1 + far),
+        });
+    }
+    for &y in [y0].iter().chain(cuts_y).chain([y1].iter()) {
+        marks.push(Mark::Cut {
+            from: (x0 - far, y),
+            to: (x0 - near, y),
+        });
+        marks.push(Mark::Cut {
+            from: (x1 + near, y),
+            to: (x1 + far, y),
+        });
+    }
+    marks
+}
+
+/// Builds the collation (back-step) mark of a signature.
+///
+/// The mark is a small bar centered on the spine fold. Each signature moves it one step
+/// further along the spine, so a correctly gathered book shows a staircase on its back.
+///
+/// # Parameters
+/// * `spine` - Outermost spine fold, from its first to its last point
+/// * `index` - Zero-based signature index
+/// * `count` - Total number of signatures
+///
+/// # Example
+/// ```
+/// use bookify_rs::marks::{collation_mark, Mark};
+///
+/// // Vertical spine running from top to bottom
+/// let mark = collation_mark(((100.0, 200.0), (100.0, 0.0)), 1, 4);
+/// assert_eq!(mark, Mark::Bar { x: 97.0, y: 172.0, width: 6.0, height: 14.0 });
+/// ```
+pub fn collation_mark(spine: ((f32, f32), (f32, f32)), index: usize, count: usize) -> Mark {
+    let (from, to) = spine;
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let spine_length = (dx * dx + dy * dy).sqrt();
+    let (ux, uy) = (dx / spine_length, dy / spine_length);
+
+    // Keep every step on the spine, even for many signatures
+    let step = COLLATION_MARK_LENGTH.min(spine_length / count.max(1) as f32);
+    let start = (
+        from.0 + ux * step * index as f32,
+        from.1 + uy * step * index as f32,
+    );
+    let end = (start.0 + ux * step, start.1 + uy * step);
+
+    // Widen the bar across the spine
+    let (x0, x1) = (start.0.min(end.0), start.0.max(end.0));
+    let (y0, y1) = (start.1.min(end.1), start.1.max(end.1));
+    let half = COLLATION_MARK_WIDTH / 2.0;
+    if dx.abs() < dy.abs() {
+        Mark::Bar {
+            x: x0 - half,
+            y: y0,
+            width: COLLATION_MARK_WIDTH,
+            height: y1 - y0,
+        }
+    } else {
+        Mark::Bar {
+            x: x0,
+            y: y0 - half,
+            width: x1 - x0,
+            height: COLLATION_MARK_WIDTH,
+        }
+    }
+}
+
+/// Font dictionary used by text marks, one of the standard 14 fonts
+pub fn mark_font() -> Dictionary {
+    let mut font = Dictionary::new();
+    font.set(b"Type", Object::Name(b"Font".to_vec()));
+    font.set(b"Subtype", Object::Name(b"Type1".to_vec()));
+    font.set(b"BaseFont", Object::Name(b"Helvetica".to_vec()));
+    font.set(b"Encoding", Object::Name(b"WinAnsiEncoding".to_vec()));
+    font
+}
+
+/// Code of a character in WinAnsiEncoding, `None` when the encoding lacks it
+fn win_ansi_code(c: char) -> Option<u8> {
+    match c as u32 {
+        0x20..=0x7E | 0xA0..=0xFF => Some(c as u8),
+        _ => WIN_ANSI_HIGH_CODES
+            .iter()
+            .find(|&&(high, _)| high == c)
+            .map(|&(_, code)| code),
+    }
+}
+
+/// Encodes text for the mark font, which prints Western European text only.
+///
+/// # Errors
+/// Returns an error listing the characters the font cannot print, such as CJK text.
+///
+/// # Example
+/// ```
+/// use bookify_rs::marks::win_ansi_encode;
+///
+/// assert_eq!(win_ansi_encode("Café – 1").unwrap(), b"Caf\xe9 \x96 1");
+/// assert!(win_ansi_encode("讲义").is_err());
+/// ```
+pub fn win_ansi_encode(text: &str) -> Result<Vec<u8>, BookifyError> {
+    // Each character is listed once, in order of appearance
+    let mut unsupported: Vec<char> = Vec::new();
+    for c in text.chars() {
+        if win_ansi_code(c).is_none() && !unsupported.contains(&c) {
+            unsupported.push(c);
+        }
+    }
+    if !unsupported.is_empty() {
+        return Err(BookifyError::invalid_argument(format!(
+            "Text '{}' has characters the mark font cannot print: {}",
+            text,
+            unsupported
+                .iter()
+                .map(|c| format!("'{}'", c))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    }
+    Ok(text.chars().filter_map(win_ansi_code).collect())
+}
+
+/// Builds the text object drawing a single line of text with the given text matrix
+fn text_operations(
+    size: f32,
+    matrix: [f32; 6],
+    text: &str,
+) -> Result<Vec<Operation>, BookifyError> {
+    let text = win_ansi_encode(text)?;
+    Ok(vec![
+        Operation::new("BT", vec![]),
+        Operation::new(
+            "Tf",
+            vec![
+                Object::Name(MARK_FONT_NAME.as_bytes().to_vec()),
+                size.into(),
+            ],
+        ),
+        Operation::new("Tm", matrix.iter().map(|&value| value.into()).collect()),
+        Operation::new("Tj", vec![Object::String(text, StringFormat::Literal)]),
+        Operation::new("ET", vec![]),
+    ])
+}
+
+/// Builds the content stream operations drawing the given marks in black
+///
+/// # Errors
+/// Returns an error when a text mark has characters the mark font cannot print.
+pub fn marks_operations(marks: &[Mark]) -> Result<Vec<Operation>, BookifyError> {
+    if marks.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut operations = vec![
+        Operation::new("q", vec![]),
+        Operation::new("w", vec![MARK_LINE_WIDTH.into()]),
+        Operation::new("G", vec![0.into()]),
+        Operation::new("g", vec![0.into()]),
+    ];
+    for mark in marks {
+        match mark {
+            Mark::Cut { .. } | Mark::Fold { .. } | Mark::Line { .. } => {
+                let dash: Vec<Object> = match mark {
+                    Mark::Fold { .. } => vec![3.into(), 2.into()],
+                    _ => vec![],
+                };
+                operations.push(Operation::new("d", vec![dash.into(), 0.into()]));
+                for (from, to) in mark.segments() {
+                    operations.push(Operation::new("m", vec![from.0.into(), from.1.into()]));
+                    operations.push(Operation::new("l", vec![to.0.into(), to.1.into()]));
+                }
+                operations.push(Operation::new("S", vec![]));
+            }
+            Mark::Bar {
+                x,
+                y,
+                width,
+                height,
+            } => {
+                operations.push(Operation::new(
+                    "re",
+                    vec![(*x).into(), (*y).into(), (*width).into(), (*height).into()],
+                ));
+                operations.push(Operation::new("f", vec![]));
+            }
+            Mark::Text { at, text } => {
+                operations.extend(text_operations(
+                    SLUG_TEXT_SIZE,
+                    [1.0, 0.0, 0.0, 1.0, at.0, at.1],
+                    text,
+                )?);
+            }
+            Mark::VerticalText { at, size, text } => {
+                // Baseline turned clockwise, glyphs face the right side
+                operations.extend(text_operations(
+                    *size,
+                    [0.0, -1.0, 1.0, 0.0, at.0, at.1],
+                    text,
+                )?);
+            }
+        }
+    }
+    operations.push(Operation::new("Q", vec![]));
+    Ok(operations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cut_segment() {
+        let mark = Mark::Cut {
+            from: (0.0, 0.0),
+            to: (100.0, 0.0),
+        };
+        assert_eq!(mark.segments(), vec![((0.0, 0.0), (100.0, 0.0))]);
+    }
+
+    #[test]
+    fn test_fold_ticks() {
+        let mark = Mark::Fold {
+            from: (50.0, 0.0),
+            to: (50.0, 100.0),
+        };
+        assert_eq!(
+            mark.segments(),
+            vec![((50.0, 0.0), (50.0, 12.0)), ((50.0, 88.0), (50.0, 100.0))]
+        );
+    }
+
+    #[test]
+    fn test_vertical_text_matrix() {
+        let operations = marks_operations(&[Mark::VerticalText {
+            at: (10.0, 200.0),
+            size: 9.0,
+            text: "Title".to_string(),
+        }])
+        .unwrap();
+        let matrix = operations
+            .iter()
+            .find(|operation| operation.operator == "Tm")
+            .unwrap();
+        let values: Vec<f32> = matrix
+            .operands
+            .iter()
+            .map(|value| value.as_float().unwrap())
+            .collect();
+        assert_eq!(values, vec![0.0, -1.0, 1.0, 0.0, 10.0, 200.0]);
+    }
+
+    #[test]
+    fn test_no_marks() {
+        assert!(marks_operations(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_text_mark_encoding() {
+        let operations = marks_operations(&[Mark::Text {
+            at: (0.0, 0.0),
+            text: "Übung “1” – Zoë".to_string(),
+        }])
+        .unwrap();
+        let text = operations
+            .iter()
+            .find(|operation| operation.operator == "Tj")
+            .unwrap();
+        assert_eq!(
+            text.operands[0].as_str().unwrap(),
+            b"\xdcbung \x931\x94 \x96 Zo\xeb"
+        );
+
+        let mark = Mark::Text {
+            at: (0.0, 0.0),
+            text: "第一讲 - Page 1/2".to_string(),
+        };
+        assert!(marks_operations(&[mark]).is_err());
+
+        let message = win_ansi_encode("讲义讲").unwrap_err().to_string();
+        assert!(message.ends_with("cannot print: '讲', '义'"));
+    }
+
+    #[test]
+    fn test_crop_marks_with_cut() {
+        let marks = crop_marks([20.0, 20.0, 120.0, 220.0], &[], &[120.0]);
+        // 2 vertical trim edges and 3 horizontal lines, ticks on both ends
+        assert_eq!(marks.len(), 10);
+        assert!(marks.contains(&Mark::Cut {
+            from: (5.0, 120.0),
+            to: (17.0, 120.0)
+        }));
+        assert!(marks.contains(&Mark::Cut {
+            from: (120.0, 223.0),
+            to: (120.0, 235.0)
+        }));
+    }
+
+    #[test]
+    fn test_collation_marks_step_along_spine() {
+        let spine = ((100.0, 200.0), (100.0, 0.0));
+        let first = collation_mark(spine, 0, 3);
+        let third = collation_mark(spine, 2, 3);
+        assert_eq!(
+            first,
+            Mark::Bar {
+                x: 97.0,
+                y: 186.0,
+                width: 6.0,
+                height: 14.0
+            }
+        );
+        assert_eq!(
+            third,
+            Mark::Bar {
+                x: 97.0,
+                y: 158.0,
+                width: 6.0,
+                height: 14.0
+            }
+        );
+    }
+
+    #[test]
+    fn test_collation_marks_horizontal_spine_shrink() {
+        // 10 signatures on a 50pt spine: steps shrink to 5pt
+        let mark = collation_mark(((0.0, 100.0), (50.0, 100.0)), 9, 10);
+        assert_eq!(
+            mark,
+            Mark::Bar {
+                x: 45.0,
+                y: 97.0,
+                width: 5.0,
+                height: 6.0
+            }
+        );
+    }
+}
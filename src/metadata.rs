@@ -0,0 +1,549 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use lopdf::{content::Content, Dictionary, Document, Object, ObjectId, Stream, StringFormat};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    args::{Binding, BindingMethod, LayoutType},
+    error::BookifyError,
+};
+
+/// Document information keys recording a previous imposition
+const IMPOSITION_KEY: &[u8] = b"BookifyImposition";
+const LAYOUT_KEY: &[u8] = b"BookifyLayout";
+const BINDING_KEY: &[u8] = b"BookifyBinding";
+const BINDING_METHOD_KEY: &[u8] = b"BookifyBindingMethod";
+const SIGNATURES_KEY: &[u8] = b"BookifySignatures";
+const SOURCE_PAGES_KEY: &[u8] = b"BookifySourcePages";
+const SHEETS_KEY: &[u8] = b"BookifySheets";
+const TOOL_KEY: &[u8] = b"BookifyTool";
+
+/// Document information keys recording the job that made an output
+const COMMAND_KEY: &[u8] = b"BookifyCommand";
+const PARAMETERS_KEY: &[u8] = b"BookifyParameters";
+const SOURCES_KEY: &[u8] = b"BookifySources";
+
+/// XMP namespace of the job properties
+pub const XMP_NAMESPACE: &str = "https://github.com/wesleyel/bookify-rs/ns/1.0/";
+
+/// Environment variable fixing the timestamps of reproducible output
+const SOURCE_DATE_EPOCH: &str = "SOURCE_DATE_EPOCH";
+
+/// Name and version of this tool, as recorded in imposed documents
+pub const TOOL_NAME: &str = concat!("bookify-rs ", env!("CARGO_PKG_VERSION"));
+
+/// Imposition recorded in the document information dictionary of an output
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Imposition {
+    /// Subcommand that imposed the document, e.g. `booklet`
+    pub kind: String,
+    /// Layout of an imposed booklet
+    pub layout: Option<LayoutType>,
+    /// Binding edge of an imposed booklet
+    pub binding: Option<Binding>,
+    /// Binding method of an imposed booklet
+    pub method: Option<BindingMethod>,
+    /// Number of sheets of each signature of an imposed booklet
+    pub signatures: Vec<u32>,
+    /// Page count of the document before imposition
+    pub source_pages: u32,
+    /// Whether pages are composed onto printed sheets, rather than reordered for the printer
+    pub sheets: bool,
+    /// Tool and version that imposed the document
+    pub tool: String,
+}
+
+impl Imposition {
+    /// Create an imposition made by this tool
+    pub fn new(kind: &str, source_pages: u32, sheets: bool) -> Self {
+        Self {
+            kind: kind.to_string(),
+            layout: None,
+            binding: None,
+            method: None,
+            signatures: Vec::new(),
+            source_pages,
+            sheets,
+            tool: TOOL_NAME.to_string(),
+        }
+    }
+
+    /// Describe the imposition for messages, e.g. `booklet (two-up, ltr, saddle) of 23 pages by bookify-rs 0.1.3`
+    pub fn describe(&self) -> String {
+        let details: Vec<String> = [
+            value_name(self.layout),
+            value_name(self.binding),
+            value_name(self.method),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        let mut description = self.kind.clone();
+        if !details.is_empty() {
+            description.push_str(&format!(" ({})", details.join(", ")));
+        }
+        format!(
+            "{} of {} pages by {}",
+            description, self.source_pages, self.tool
+        )
+    }
+}
+
+/// Command line name of a value enum
+fn value_name<T: ValueEnum>(value: Option<T>) -> Option<String> {
+    Some(value?.to_possible_value()?.get_name().to_string())
+}
+
+/// Document information dictionary, if any
+fn info_dictionary(doc: &Document) -> Option<&Dictionary> {
+    match doc.trailer.get(b"Info").ok()? {
+        Object::Reference(id) => doc.get_dictionary(*id).ok(),
+        Object::Dictionary(dict) => Some(dict),
+        _ => None,
+    }
+}
+
+/// Document information dictionary, created when missing
+fn info_dictionary_mut(doc: &mut Document) -> Result<&mut Dictionary, BookifyError> {
+    let info_id: ObjectId = match doc.trailer.get(b"Info") {
+        Ok(Object::Reference(id)) => *id,
+        Ok(Object::Dictionary(dict)) => {
+            let dict = dict.clone();
+            doc.add_object(dict)
+        }
+        _ => doc.add_object(Dictionary::new()),
+    };
+    doc.trailer.set("Info", Object::Reference(info_id));
+    Ok(doc.get_dictionary_mut(info_id)?)
+}
+
+/// PDF text string: a literal for ASCII text, UTF-16BE with a byte order mark otherwise
+fn text_string(text: &str) -> Object {
+    if text.is_ascii() {
+        Object::string_literal(text)
+    } else {
+        let mut bytes = vec![0xfe, 0xff];
+        bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+        Object::String(bytes, StringFormat::Hexadecimal)
+    }
+}
+
+/// Text value of a document information entry
+fn info_text(info: &Dictionary, key: &[u8]) -> Option<String> {
+    let bytes = info.get(key).and_then(Object::as_str).ok()?;
+    match bytes {
+        [0xfe, 0xff, utf16 @ ..] => {
+            let units: Vec<u16> = utf16
+                .chunks_exact(2)
+                .map(|unit| u16::from_be_bytes([unit[0], unit[1]]))
+                .collect();
+            Some(String::from_utf16_lossy(&units))
+        }
+        _ => Some(String::from_utf8_lossy(bytes).into_owned()),
+    }
+}
+
+/// Read the imposition recorded in a document, if any
+pub fn read_imposition(doc: &Document) -> Option<Imposition> {
+    let info = info_dictionary(doc)?;
+    let kind = info_text(info, IMPOSITION_KEY)?;
+    let parse = |key| info_text(info, key);
+    Some(Imposition {
+        kind,
+        layout: parse(LAYOUT_KEY).and_then(|name| LayoutType::from_str(&name, false).ok()),
+        binding: parse(BINDING_KEY).and_then(|name| Binding::from_str(&name, false).ok()),
+        method: parse(BINDING_METHOD_KEY)
+            .and_then(|name| BindingMethod::from_str(&name, false).ok()),
+        signatures: parse(SIGNATURES_KEY)
+            .and_then(|sizes| sizes.split('+').map(|size| size.parse().ok()).collect())
+            .unwrap_or_default(),
+        source_pages: parse(SOURCE_PAGES_KEY)
+            .and_then(|pages| pages.parse().ok())
+            .unwrap_or(0),
+        sheets: parse(SHEETS_KEY).is_some_and(|sheets| sheets == "true"),
+        tool: parse(TOOL_KEY).unwrap_or_default(),
+    })
+}
+
+/// Record an imposition in a document, or remove a recorded one with `None`
+pub fn write_imposition(
+    doc: &mut Document,
+    imposition: Option<&Imposition>,
+) -> Result<(), BookifyError> {
+    if imposition.is_none() && info_dictionary(doc).is_none() {
+        return Ok(());
+    }
+
+    let info = info_dictionary_mut(doc)?;
+    for key in [
+        IMPOSITION_KEY,
+        LAYOUT_KEY,
+        BINDING_KEY,
+        BINDING_METHOD_KEY,
+        SIGNATURES_KEY,
+        SOURCE_PAGES_KEY,
+        SHEETS_KEY,
+        TOOL_KEY,
+    ] {
+        info.remove(key);
+    }
+    if let Some(imposition) = imposition {
+        info.set(IMPOSITION_KEY, text_string(&imposition.kind));
+        info.set(
+            SOURCE_PAGES_KEY,
+            text_string(&imposition.source_pages.to_string()),
+        );
+        info.set(SHEETS_KEY, text_string(&imposition.sheets.to_string()));
+        info.set(TOOL_KEY, text_string(&imposition.tool));
+        if let Some(layout) = value_name(imposition.layout) {
+            info.set(LAYOUT_KEY, text_string(&layout));
+        }
+        if let Some(binding) = value_name(imposition.binding) {
+            info.set(BINDING_KEY, text_string(&binding));
+        }
+        if let Some(method) = value_name(imposition.method) {
+            info.set(BINDING_METHOD_KEY, text_string(&method));
+        }
+        if !imposition.signatures.is_empty() {
+            let sizes: Vec<String> = imposition.signatures.iter().map(u32::to_string).collect();
+            info.set(SIGNATURES_KEY, text_string(&sizes.join("+")));
+        }
+    }
+    Ok(())
+}
+
+/// Input file an output was made from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Source {
+    /// File name, without its directory
+    pub name: String,
+    /// SHA-256 of the file content, in hexadecimal
+    pub sha256: String,
+}
+
+impl Source {
+    /// Describe a loaded input file
+    pub fn new(path: &Path, content: &[u8]) -> Self {
+        Self {
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            sha256: format!("{:x}", Sha256::digest(content)),
+        }
+    }
+}
+
+/// Command line of the job that makes an output
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Job {
+    /// Subcommand, e.g. `booklet`
+    pub command: String,
+    /// Options of the subcommand as name and value, defaults included
+    pub parameters: Vec<(String, String)>,
+}
+
+/// Provenance of an output: what it was made from, how, when and by which tool
+#[derive(Clone, Debug)]
+pub struct Provenance<'a> {
+    pub sources: &'a [Source],
+    pub job: &'a Job,
+    pub imposition: Option<&'a Imposition>,
+    pub created: DateTime<Utc>,
+}
+
+/// Escape text for XML content and attribute values
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Builds the XMP packet describing an output
+fn xmp_packet(provenance: &Provenance, title: Option<&str>) -> String {
+    let date = provenance
+        .created
+        .to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let mut properties = vec![
+        format!("<xmp:CreateDate>{}</xmp:CreateDate>", date),
+        format!("<xmp:ModifyDate>{}</xmp:ModifyDate>", date),
+        format!("<xmp:MetadataDate>{}</xmp:MetadataDate>", date),
+        format!("<pdf:Producer>{}</pdf:Producer>", xml_escape(TOOL_NAME)),
+        "<dc:format>application/pdf</dc:format>".to_string(),
+    ];
+    if let Some(title) = title {
+        properties.push(format!(
+            "<dc:title><rdf:Alt><rdf:li xml:lang=\"x-default\">{}</rdf:li></rdf:Alt></dc:title>",
+            xml_escape(title)
+        ));
+    }
+    if !provenance.job.command.is_empty() {
+        properties.push(format!(
+            "<bookify:Command>{}</bookify:Command>",
+            xml_escape(&provenance.job.command)
+        ));
+    }
+    if !provenance.job.parameters.is_empty() {
+        let items: String = provenance
+            .job
+            .parameters
+            .iter()
+            .map(|(name, value)| {
+                format!(
+                    "<rdf:li>{}={}</rdf:li>",
+                    xml_escape(name),
+                    xml_escape(value)
+                )
+            })
+            .collect();
+        properties.push(format!(
+            "<bookify:Parameters><rdf:Bag>{}</rdf:Bag></bookify:Parameters>",
+            items
+        ));
+    }
+    if !provenance.sources.is_empty() {
+        let items: String = provenance
+            .sources
+            .iter()
+            .map(|source| {
+                format!(
+                    "<rdf:li rdf:parseType=\"Resource\"><bookify:Name>{}</bookify:Name>\
+                     <bookify:SHA256>{}</bookify:SHA256></rdf:li>",
+                    xml_escape(&source.name),
+                    source.sha256
+                )
+            })
+            .collect();
+        properties.push(format!(
+            "<bookify:Sources><rdf:Seq>{}</rdf:Seq></bookify:Sources>",
+            items
+        ));
+    }
+    if let Some(imposition) = provenance.imposition {
+        properties.push(format!(
+            "<bookify:Imposition>{}</bookify:Imposition>",
+            xml_escape(&imposition.describe())
+        ));
+    }
+
+    let mut packet =
+        String::from("<?xpacket begin=\"\u{feff}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\n");
+    packet.push_str("<x:xmpmeta xmlns:x=\"adobe:ns:meta/\">\n");
+    packet.push_str(" <rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n");
+    packet.push_str(&format!(
+        "  <rdf:Description rdf:about=\"\" xmlns:xmp=\"http://ns.adobe.com/xap/1.0/\" \
+         xmlns:pdf=\"http://ns.adobe.com/pdf/1.3/\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\" \
+         xmlns:bookify=\"{}\">\n",
+        XMP_NAMESPACE
+    ));
+    for property in properties {
+        packet.push_str(&format!("   {}\n", property));
+    }
+    packet.push_str("  </rdf:Description>\n </rdf:RDF>\n</x:xmpmeta>\n<?xpacket end=\"w\"?>");
+    packet
+}
+
+/// Record the provenance of an output in the document information dictionary and in
+/// the XMP metadata of the catalog, and make this tool the producer
+pub fn write_provenance(doc: &mut Document, provenance: &Provenance) -> Result<(), BookifyError> {
+    let date = text_string(&provenance.created.format("D:%Y%m%d%H%M%SZ").to_string());
+    let info = info_dictionary_mut(doc)?;
+    info.set("Producer", text_string(TOOL_NAME));
+    info.set("CreationDate", date.clone());
+    info.set("ModDate", date);
+    info.remove(COMMAND_KEY);
+    info.remove(PARAMETERS_KEY);
+    info.remove(SOURCES_KEY);
+    if !provenance.job.command.is_empty() {
+        info.set(COMMAND_KEY, text_string(&provenance.job.command));
+    }
+    if !provenance.job.parameters.is_empty() {
+        let parameters: Vec<String> = provenance
+            .job
+            .parameters
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        info.set(PARAMETERS_KEY, text_string(&parameters.join("; ")));
+    }
+    if !provenance.sources.is_empty() {
+        let sources: Vec<String> = provenance
+            .sources
+            .iter()
+            .map(|source| format!("{} (sha256 {})", source.name, source.sha256))
+            .collect();
+        info.set(SOURCES_KEY, text_string(&sources.join("; ")));
+    }
+    let title = info_text(info, b"Title").filter(|title| !title.is_empty());
+
+    let mut dict = Dictionary::new();
+    dict.set("Type", Object::Name(b"Metadata".to_vec()));
+    dict.set("Subtype", Object::Name(b"XML".to_vec()));
+    let mut stream = Stream::new(dict, xmp_packet(provenance, title.as_deref()).into_bytes());
+    // Metadata stays readable by tools that do not parse PDF
+    stream.allows_compression = false;
+    let metadata_id = doc.add_object(stream);
+    doc.catalog_mut()?
+        .set("Metadata", Object::Reference(metadata_id));
+    Ok(())
+}
+
+/// Timestamp recorded in reproducible output: `SOURCE_DATE_EPOCH` when set,
+/// the Unix epoch otherwise
+pub fn reproducible_timestamp() -> Result<DateTime<Utc>, BookifyError> {
+    match std::env::var(SOURCE_DATE_EPOCH) {
+        Ok(value) => parse_source_date_epoch(&value),
+        Err(_) => Ok(DateTime::UNIX_EPOCH),
+    }
+}
+
+/// Parse a `SOURCE_DATE_EPOCH` value, in seconds since the Unix epoch
+fn parse_source_date_epoch(value: &str) -> Result<DateTime<Utc>, BookifyError> {
+    value
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        .ok_or_else(|| {
+            BookifyError::invalid_argument(format!(
+                "{} must be a number of seconds since the Unix epoch, got '{}'",
+                SOURCE_DATE_EPOCH, value
+            ))
+        })
+}
+
+/// Set the trailer /ID to a digest of the document as it would be saved, so
+/// that identical documents get identical identifiers
+pub fn write_content_id(doc: &mut Document) -> Result<(), BookifyError> {
+    doc.trailer.remove(b"ID");
+    let mut content = Vec::new();
+    doc.save_to(&mut content)
+        .map_err(|e| BookifyError::pdf_processing_failed("derive document ID", e.to_string()))?;
+    let id = Object::String(
+        Sha256::digest(&content)[..16].to_vec(),
+        StringFormat::Hexadecimal,
+    );
+    doc.trailer.set("ID", Object::Array(vec![id.clone(), id]));
+    Ok(())
+}
+
+/// Whether every page draws nothing but several placed Form XObjects, the way
+/// imposition tools compose pages onto sheets
+pub fn has_composed_pages(doc: &Document) -> bool {
+    let pages = doc.get_pages();
+    !pages.is_empty()
+        && pages
+            .values()
+            .all(|&page_id| placed_forms(doc, page_id).is_some_and(|forms| forms >= 2))
+}
+
+/// Number of Form XObjects a page places, `None` when it also draws text or images
+fn placed_forms(doc: &Document, page_id: ObjectId) -> Option<usize> {
+    let content = Content::decode(&doc.get_page_content(page_id).ok()?).ok()?;
+    let (resources, _) = doc.get_page_resources(page_id);
+    let xobjects = match resources?.get(b"XObject").ok()? {
+        Object::Reference(id) => doc.get_dictionary(*id).ok()?,
+        Object::Dictionary(dict) => dict,
+        _ => return None,
+    };
+
+    let mut forms = 0;
+    for operation in &content.operations {
+        match operation.operator.as_str() {
+            "Do" => {
+                let name = operation.operands.first()?.as_name().ok()?;
+                let xobject = doc
+                    .get_object(xobjects.get(name).ok()?.as_reference().ok()?)
+                    .ok()?
+                    .as_stream()
+                    .ok()?;
+                if xobject
+                    .dict
+                    .get(b"Subtype")
+                    .and_then(Object::as_name)
+                    .ok()?
+                    != b"Form"
+                {
+                    return None;
+                }
+                forms += 1;
+            }
+            "Tj" | "TJ" | "'" | "\"" | "BI" => return None,
+            _ => {}
+        }
+    }
+    Some(forms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_string_round_trip() {
+        let mut info = Dictionary::new();
+        info.set("Title", text_string("小册子 & zine"));
+        assert!(matches!(
+            info.get(b"Title").unwrap(),
+            Object::String(_, StringFormat::Hexadecimal)
+        ));
+        assert_eq!(info_text(&info, b"Title").unwrap(), "小册子 & zine");
+    }
+
+    #[test]
+    fn test_source_digest() {
+        let source = Source::new(Path::new("slides/deck.pdf"), b"abc");
+        assert_eq!(source.name, "deck.pdf");
+        assert_eq!(
+            source.sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_imposition_round_trip() {
+        let mut doc = Document::with_version("1.5");
+        let imposition = Imposition {
+            layout: Some(LayoutType::TwoUp),
+            binding: Some(Binding::Top),
+            method: Some(BindingMethod::Saddle),
+            signatures: vec![2, 1],
+            ..Imposition::new("booklet", 12, true)
+        };
+        write_imposition(&mut doc, Some(&imposition)).unwrap();
+        assert_eq!(read_imposition(&doc), Some(imposition));
+        write_imposition(&mut doc, None).unwrap();
+        assert_eq!(read_imposition(&doc), None);
+    }
+
+    #[test]
+    fn test_parse_source_date_epoch() {
+        assert_eq!(
+            parse_source_date_epoch("1700000000\n").unwrap(),
+            DateTime::from_timestamp(1_700_000_000, 0).unwrap()
+        );
+        assert!(parse_source_date_epoch("yesterday").is_err());
+        assert!(parse_source_date_epoch("").is_err());
+    }
+
+    #[test]
+    fn test_xmp_escapes_text() {
+        let job = Job {
+            command: "grid".to_string(),
+            parameters: vec![("pages".to_string(), "1-4".to_string())],
+        };
+        let provenance = Provenance {
+            sources: &[],
+            job: &job,
+            imposition: None,
+            created: DateTime::from_timestamp(0, 0).unwrap(),
+        };
+        let packet = xmp_packet(&provenance, Some("Cats & <Dogs>"));
+        assert!(packet.contains("Cats &amp; &lt;Dogs&gt;"));
+        assert!(packet.contains("<xmp:CreateDate>1970-01-01T00:00:00Z</xmp:CreateDate>"));
+        assert!(!packet.contains("bookify:Sources"));
+    }
+}
@@ -0,0 +1,658 @@
+use std::collections::BTreeMap;
+
+use lopdf::{Dictionary, Document, Object, ObjectId, StringFormat};
+
+use crate::{error::BookifyError, sheet::transform_point as transform};
+
+/// Depth after which name, number and outline trees are assumed to loop
+const MAX_TREE_DEPTH: u32 = 32;
+
+/// Where a source page went when the page tree was rebuilt
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PageMove {
+    /// Page now showing the source page
+    pub page: ObjectId,
+    /// Matrix placing the source page on a sheet, `None` when it is still a page of its own
+    pub matrix: Option<[f32; 6]>,
+}
+
+/// Source pages still shown in the document, by their former page object
+pub type PageMoves = BTreeMap<ObjectId, PageMove>;
+
+/// Resolve a destination (explicit array, or name in /Dests or the /Names tree) to its page
+pub fn destination_page(doc: &Document, dest: &Object) -> Option<ObjectId> {
+    match dest {
+        Object::Array(array) => array.first()?.as_reference().ok(),
+        Object::Reference(id) => destination_page(doc, doc.get_object(*id).ok()?),
+        Object::Dictionary(dict) => destination_page(doc, dict.get(b"D").ok()?),
+        Object::Name(name) | Object::String(name, _) => {
+            destination_page(doc, named_destination(doc, name)?)
+        }
+        _ => None,
+    }
+}
+
+/// Look up a named destination in /Dests, then in the /Names tree
+fn named_destination<'a>(doc: &'a Document, name: &[u8]) -> Option<&'a Object> {
+    let catalog = doc.catalog().ok()?;
+    let dests = catalog
+        .get(b"Dests")
+        .and_then(|dests| doc.dereference(dests))
+        .and_then(|(_, dests)| dests.as_dict())
+        .ok();
+    if let Some(dest) = dests.and_then(|dests| dests.get(name).ok()) {
+        return Some(dest);
+    }
+    let tree = catalog
+        .get(b"Names")
+        .and_then(|names| doc.dereference(names))
+        .and_then(|(_, names)| names.as_dict())
+        .and_then(|names| names.get(b"Dests"))
+        .ok()?;
+    name_tree_entries(doc, tree, 0)
+        .into_iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value)
+}
+
+/// All entries of a name tree, in key order
+fn name_tree_entries<'a>(
+    doc: &'a Document,
+    node: &'a Object,
+    depth: u32,
+) -> Vec<(Vec<u8>, &'a Object)> {
+    let Some(node) = doc
+        .dereference(node)
+        .and_then(|(_, node)| node.as_dict())
+        .ok()
+        .filter(|_| depth <= MAX_TREE_DEPTH)
+    else {
+        return Vec::new();
+    };
+    if let Ok(names) = node.get(b"Names").and_then(Object::as_array) {
+        return names
+            .chunks_exact(2)
+            .filter_map(|pair| match &pair[0] {
+                Object::String(key, _) => Some((key.clone(), &pair[1])),
+                _ => None,
+            })
+            .collect();
+    }
+    node.get(b"Kids")
+        .and_then(Object::as_array)
+        .map(|kids| {
+            kids.iter()
+                .flat_map(|kid| name_tree_entries(doc, kid, depth + 1))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// All entries of a number tree, in key order
+fn number_tree_entries<'a>(
+    doc: &'a Document,
+    node: &'a Object,
+    depth: u32,
+) -> Vec<(i64, &'a Object)> {
+    let Some(node) = doc
+        .dereference(node)
+        .and_then(|(_, node)| node.as_dict())
+        .ok()
+        .filter(|_| depth <= MAX_TREE_DEPTH)
+    else {
+        return Vec::new();
+    };
+    if let Ok(nums) = node.get(b"Nums").and_then(Object::as_array) {
+        return nums
+            .chunks_exact(2)
+            .filter_map(|pair| Some((pair[0].as_i64().ok()?, &pair[1])))
+            .collect();
+    }
+    node.get(b"Kids")
+        .and_then(Object::as_array)
+        .map(|kids| {
+            kids.iter()
+                .flat_map(|kid| number_tree_entries(doc, kid, depth + 1))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Replace a catalog entry, in place when the catalog refers to it
+fn set_catalog_entry(doc: &mut Document, key: &[u8], value: Object) -> Result<(), BookifyError> {
+    match doc.catalog()?.get(key) {
+        Ok(&Object::Reference(id)) => {
+            doc.objects.insert(id, value);
+        }
+        _ => doc.catalog_mut()?.set(key, value),
+    }
+    Ok(())
+}
+
+/// Destination view on the page now showing a source page. On a sheet, a point
+/// or rectangle of the source page is placed with the page, other views fit the sheet.
+fn moved_view(view: &[Object], page_move: &PageMove) -> Vec<Object> {
+    let page = Object::Reference(page_move.page);
+    let Some(matrix) = page_move.matrix else {
+        return std::iter::once(page)
+            .chain(view.iter().skip(1).cloned())
+            .collect();
+    };
+    let number = |index: usize| view.get(index).and_then(|value| value.as_float().ok());
+    match view.get(1).and_then(|fit| fit.as_name().ok()) {
+        Some(b"XYZ") => {
+            if let (Some(left), Some(top)) = (number(2), number(3)) {
+                let (x, y) = transform(matrix, left, top);
+                let zoom = view.get(4).cloned().unwrap_or(Object::Null);
+                return vec![
+                    page,
+                    Object::Name(b"XYZ".to_vec()),
+                    x.into(),
+                    y.into(),
+                    zoom,
+                ];
+            }
+        }
+        Some(b"FitR") => {
+            if let (Some(x0), Some(y0), Some(x1), Some(y1)) =
+                (number(2), number(3), number(4), number(5))
+            {
+                let (ax, ay) = transform(matrix, x0, y0);
+                let (bx, by) = transform(matrix, x1, y1);
+                return vec![
+                    page,
+                    Object::Name(b"FitR".to_vec()),
+                    ax.min(bx).into(),
+                    ay.min(by).into(),
+                    ax.max(bx).into(),
+                    ay.max(by).into(),
+                ];
+            }
+        }
+        _ => {}
+    }
+    vec![page, Object::Name(b"Fit".to_vec())]
+}
+
+/// Rewrite a destination for the rebuilt page tree, `None` when its page is gone.
+/// Named destinations are kept while their name is still defined.
+fn remap_destination(doc: &Document, dest: &Object, moves: &PageMoves) -> Option<Object> {
+    match dest {
+        Object::Array(view) => match view.first()? {
+            Object::Reference(page_id) => {
+                Some(Object::Array(moved_view(view, moves.get(page_id)?)))
+            }
+            _ => Some(dest.clone()),
+        },
+        Object::Reference(id) => remap_destination(doc, doc.get_object(*id).ok()?, moves),
+        Object::Dictionary(dict) => {
+            let target = remap_destination(doc, dict.get(b"D").ok()?, moves)?;
+            let mut dict = dict.clone();
+            dict.set("D", target);
+            Some(Object::Dictionary(dict))
+        }
+        Object::Name(name) | Object::String(name, _) => {
+            named_destination(doc, name).map(|_| dest.clone())
+        }
+        _ => None,
+    }
+}
+
+/// Point the destination or go-to action of an outline item or link at the
+/// rebuilt page tree. Returns false when its page is gone, the target is then removed.
+fn remap_target(doc: &Document, item: &mut Dictionary, moves: &PageMoves) -> bool {
+    if let Ok(dest) = item.get(b"Dest") {
+        return match remap_destination(doc, dest, moves) {
+            Some(dest) => {
+                item.set("Dest", dest);
+                true
+            }
+            None => {
+                item.remove(b"Dest");
+                false
+            }
+        };
+    }
+
+    let action = item
+        .get(b"A")
+        .and_then(|action| doc.dereference(action))
+        .and_then(|(_, action)| action.as_dict())
+        .ok()
+        .filter(|action| action.get(b"S").and_then(Object::as_name).ok() == Some(b"GoTo"))
+        .cloned();
+    let Some(mut action) = action else {
+        return true;
+    };
+    match action
+        .get(b"D")
+        .ok()
+        .and_then(|dest| remap_destination(doc, dest, moves))
+    {
+        Some(dest) => {
+            action.set("D", dest);
+            item.set("A", Object::Dictionary(action));
+            true
+        }
+        None => {
+            item.remove(b"A");
+            false
+        }
+    }
+}
+
+/// Rewrite the /Dests dictionary and the /Names destination tree, dropping
+/// destinations whose page is gone
+fn remap_named_destinations(doc: &mut Document, moves: &PageMoves) -> Result<(), BookifyError> {
+    let dests = doc
+        .catalog()?
+        .get(b"Dests")
+        .and_then(|dests| doc.dereference(dests))
+        .and_then(|(_, dests)| dests.as_dict())
+        .ok();
+    if let Some(dests) = dests {
+        let mut remapped = Dictionary::new();
+        for (name, dest) in dests.iter() {
+            if let Some(dest) = remap_destination(doc, dest, moves) {
+                remapped.set(name.clone(), dest);
+            }
+        }
+        set_catalog_entry(doc, b"Dests", Object::Dictionary(remapped))?;
+    }
+
+    let names = doc
+        .catalog()?
+        .get(b"Names")
+        .and_then(|names| doc.dereference(names))
+        .and_then(|(_, names)| names.as_dict())
+        .ok();
+    if let Some(tree) = names.and_then(|names| names.get(b"Dests").ok()) {
+        // The rebuilt tree is a single node
+        let mut entries = Vec::new();
+        for (name, dest) in name_tree_entries(doc, tree, 0) {
+            if let Some(dest) = remap_destination(doc, dest, moves) {
+                entries.push(Object::String(name, StringFormat::Literal));
+                entries.push(dest);
+            }
+        }
+        let mut tree = Dictionary::new();
+        tree.set("Names", Object::Array(entries));
+        let mut names = names.cloned().unwrap_or_default();
+        names.set("Dests", Object::Dictionary(tree));
+        set_catalog_entry(doc, b"Names", Object::Dictionary(names))?;
+    }
+    Ok(())
+}
+
+/// Rewrite the outline items below `parent_id`, dropping items whose page is gone
+/// unless items below them remain. Returns the number of items shown below the
+/// parent when it is open.
+fn remap_outline_items(
+    doc: &mut Document,
+    parent_id: ObjectId,
+    moves: &PageMoves,
+    depth: u32,
+) -> Result<i64, BookifyError> {
+    let mut items = Vec::new();
+    let mut next = doc
+        .get_dictionary(parent_id)?
+        .get(b"First")
+        .and_then(Object::as_reference)
+        .ok();
+    while let Some(id) = next.filter(|id| !items.contains(id) && depth <= MAX_TREE_DEPTH) {
+        items.push(id);
+        next = doc
+            .get_dictionary(id)
+            .and_then(|item| item.get(b"Next"))
+            .and_then(Object::as_reference)
+            .ok();
+    }
+
+    let mut kept = Vec::with_capacity(items.len());
+    let mut shown = 0;
+    for item_id in items {
+        let Ok(item) = doc.get_dictionary(item_id) else {
+            continue;
+        };
+        let mut item = item.clone();
+        let below = remap_outline_items(doc, item_id, moves, depth + 1)?;
+        let has_target = remap_target(doc, &mut item, moves);
+        if !has_target && below == 0 {
+            continue;
+        }
+
+        // A negative count marks a closed item
+        let open = item.get(b"Count").and_then(Object::as_i64).unwrap_or(0) >= 0;
+        if below > 0 {
+            item.set("Count", Object::Integer(if open { below } else { -below }));
+        } else {
+            item.remove(b"Count");
+            item.remove(b"First");
+            item.remove(b"Last");
+        }
+        doc.objects.insert(item_id, Object::Dictionary(item));
+        kept.push(item_id);
+        shown += 1 + if open { below } else { 0 };
+    }
+
+    for (index, &item_id) in kept.iter().enumerate() {
+        let item = doc.get_dictionary_mut(item_id)?;
+        match index.checked_sub(1) {
+            Some(previous) => item.set("Prev", Object::Reference(kept[previous])),
+            None => {
+                item.remove(b"Prev");
+            }
+        }
+        match kept.get(index + 1) {
+            Some(&next) => item.set("Next", Object::Reference(next)),
+            None => {
+                item.remove(b"Next");
+            }
+        }
+    }
+    let parent = doc.get_dictionary_mut(parent_id)?;
+    match (kept.first(), kept.last()) {
+        (Some(&first), Some(&last)) => {
+            parent.set("First", Object::Reference(first));
+            parent.set("Last", Object::Reference(last));
+        }
+        _ => {
+            parent.remove(b"First");
+            parent.remove(b"Last");
+        }
+    }
+    Ok(shown)
+}
+
+/// Rewrite the document outline, removing it when no item is left
+fn remap_outline(doc: &mut Document, moves: &PageMoves) -> Result<(), BookifyError> {
+    let Ok(outlines_id) = doc
+        .catalog()?
+        .get(b"Outlines")
+        .and_then(Object::as_reference)
+    else {
+        return Ok(());
+    };
+    if doc.get_dictionary(outlines_id).is_err() {
+        return Ok(());
+    }
+    let shown = remap_outline_items(doc, outlines_id, moves, 0)?;
+    if doc.get_dictionary(outlines_id)?.has(b"First") {
+        doc.get_dictionary_mut(outlines_id)?
+            .set("Count", Object::Integer(shown));
+    } else {
+        doc.catalog_mut()?.remove(b"Outlines");
+    }
+    Ok(())
+}
+
+/// Rewrite the destination opened with the document, removing it when its page is gone
+fn remap_open_action(doc: &mut Document, moves: &PageMoves) -> Result<(), BookifyError> {
+    let Ok(open_action) = doc.catalog()?.get(b"OpenAction") else {
+        return Ok(());
+    };
+    let Ok((_, open_action)) = doc.dereference(open_action) else {
+        return Ok(());
+    };
+    let remapped = match open_action {
+        Object::Array(_) => remap_destination(doc, open_action, moves),
+        Object::Dictionary(_) => {
+            let mut holder = Dictionary::new();
+            holder.set("A", open_action.clone());
+            remap_target(doc, &mut holder, moves)
+                .then(|| holder.remove(b"A"))
+                .flatten()
+        }
+        _ => return Ok(()),
+    };
+    match remapped {
+        Some(open_action) => doc.catalog_mut()?.set("OpenAction", open_action),
+        None => {
+            doc.catalog_mut()?.remove(b"OpenAction");
+        }
+    }
+    Ok(())
+}
+
+/// Rewrite the links on every page, removing links whose page is gone
+fn remap_links(doc: &mut Document, moves: &PageMoves) -> Result<(), BookifyError> {
+    for page_id in doc.get_pages().into_values() {
+        let annots = doc
+            .get_dictionary(page_id)?
+            .get(b"Annots")
+            .and_then(|annots| doc.dereference(annots))
+            .and_then(|(_, annots)| annots.as_array())
+            .ok()
+            .cloned();
+        let Some(annots) = annots else {
+            continue;
+        };
+
+        let mut kept = Vec::with_capacity(annots.len());
+        for annot in annots {
+            let (annot_id, dict) = match &annot {
+                Object::Reference(id) => (Some(*id), doc.get_dictionary(*id).ok()),
+                Object::Dictionary(dict) => (None, Some(dict)),
+                _ => (None, None),
+            };
+            let Some(mut dict) = dict
+                .filter(|dict| dict.get(b"Subtype").and_then(Object::as_name).ok() == Some(b"Link"))
+                .cloned()
+            else {
+                kept.push(annot);
+                continue;
+            };
+            if !remap_target(doc, &mut dict, moves) {
+                continue;
+            }
+            match annot_id {
+                Some(id) => {
+                    doc.objects.insert(id, Object::Dictionary(dict));
+                    kept.push(annot);
+                }
+                None => kept.push(Object::Dictionary(dict)),
+            }
+        }
+
+        let page = doc.get_dictionary_mut(page_id)?;
+        if kept.is_empty() {
+            page.remove(b"Annots");
+        } else {
+            page.set("Annots", Object::Array(kept));
+        }
+    }
+    Ok(())
+}
+
+/// Point named destinations, outline items, the open action and links at the
+/// pages now showing their source pages, dropping those whose page is gone
+pub fn remap_page_references(doc: &mut Document, moves: &PageMoves) -> Result<(), BookifyError> {
+    // Outline items and links to a name need the remapped names
+    remap_named_destinations(doc, moves)?;
+    remap_outline(doc, moves)?;
+    remap_open_action(doc, moves)?;
+    remap_links(doc, moves)
+}
+
+/// Label of one page: numbering style, prefix and number
+#[derive(Clone, Debug, PartialEq)]
+struct PageLabel {
+    style: Option<Vec<u8>>,
+    prefix: Option<Vec<u8>>,
+    number: i64,
+}
+
+impl Default for PageLabel {
+    fn default() -> Self {
+        Self {
+            style: None,
+            prefix: None,
+            number: 1,
+        }
+    }
+}
+
+impl PageLabel {
+    /// Label of the page in a label range, `offset` pages after its start
+    fn in_range(range: &Dictionary, offset: i64) -> Self {
+        Self {
+            style: range
+                .get(b"S")
+                .and_then(Object::as_name)
+                .ok()
+                .map(<[u8]>::to_vec),
+            prefix: range
+                .get(b"P")
+                .and_then(Object::as_str)
+                .ok()
+                .map(<[u8]>::to_vec),
+            number: range.get(b"St").and_then(Object::as_i64).unwrap_or(1) + offset,
+        }
+    }
+
+    /// Label of the following page in the same range
+    fn next(&self) -> Self {
+        Self {
+            number: self.number + 1,
+            ..self.clone()
+        }
+    }
+
+    /// Label range dictionary starting with this label
+    fn range(&self) -> Dictionary {
+        let mut range = Dictionary::new();
+        if let Some(style) = &self.style {
+            range.set("S", Object::Name(style.clone()));
+        }
+        if let Some(prefix) = &self.prefix {
+            range.set("P", Object::String(prefix.clone(), StringFormat::Literal));
+        }
+        if self.number != 1 {
+            range.set("St", Object::Integer(self.number));
+        }
+        range
+    }
+}
+
+/// Rebuild the page labels for a new page order, where 0 is a blank page.
+/// Pages keep their labels, blank pages continue the numbering of the page before them.
+pub fn remap_page_labels(doc: &mut Document, new_order: &[u32]) -> Result<(), BookifyError> {
+    let Ok(tree) = doc.catalog()?.get(b"PageLabels") else {
+        return Ok(());
+    };
+    let ranges: Vec<(i64, Dictionary)> = number_tree_entries(doc, tree, 0)
+        .into_iter()
+        .filter_map(|(start, range)| {
+            let (_, range) = doc.dereference(range).ok()?;
+            Some((start, range.as_dict().ok()?.clone()))
+        })
+        .collect();
+    let label_of = |page: u32| {
+        let index = page as i64 - 1;
+        ranges
+            .iter()
+            .rev()
+            .find(|(start, _)| *start <= index)
+            .map(|(start, range)| PageLabel::in_range(range, index - start))
+            .unwrap_or_default()
+    };
+
+    let mut nums = Vec::new();
+    let mut previous: Option<PageLabel> = None;
+    for (index, &page) in new_order.iter().enumerate() {
+        let continued = previous.as_ref().map(PageLabel::next);
+        let label = match page {
+            0 => continued.clone().unwrap_or_default(),
+            page => label_of(page),
+        };
+        if continued.as_ref() != Some(&label) {
+            nums.push(Object::Integer(index as i64));
+            nums.push(Object::Dictionary(label.range()));
+        }
+        previous = Some(label);
+    }
+
+    let mut tree = Dictionary::new();
+    tree.set("Nums", Object::Array(nums));
+    set_catalog_entry(doc, b"PageLabels", Object::Dictionary(tree))
+}
+
+/// Remove the page labels, which do not apply to composed sheets
+pub fn remove_page_labels(doc: &mut Document) -> Result<(), BookifyError> {
+    doc.catalog_mut()?.remove(b"PageLabels");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_moved_view_on_sheet() {
+        let page_move = PageMove {
+            page: (7, 0),
+            matrix: Some([0.5, 0.0, 0.0, 0.5, 100.0, 20.0]),
+        };
+        let view = vec![
+            Object::Reference((3, 0)),
+            Object::Name(b"XYZ".to_vec()),
+            Object::Integer(40),
+            Object::Real(200.0),
+            Object::Null,
+        ];
+        let moved = moved_view(&view, &page_move);
+        assert_eq!(moved[0].as_reference().unwrap(), (7, 0));
+        assert_eq!(moved[1].as_name().unwrap(), b"XYZ");
+        assert_eq!(moved[2].as_float().unwrap(), 120.0);
+        assert_eq!(moved[3].as_float().unwrap(), 120.0);
+
+        // Views without a point fit the sheet
+        let view = vec![Object::Reference((3, 0)), Object::Name(b"FitH".to_vec())];
+        let moved = moved_view(&view, &page_move);
+        assert_eq!(moved.len(), 2);
+        assert_eq!(moved[1].as_name().unwrap(), b"Fit");
+    }
+
+    #[test]
+    fn test_remap_page_labels() {
+        let mut doc = Document::with_version("1.5");
+        let catalog_id = doc.add_object(Dictionary::new());
+        doc.trailer.set("Root", Object::Reference(catalog_id));
+        let mut roman = Dictionary::new();
+        roman.set("S", Object::Name(b"r".to_vec()));
+        let mut decimal = Dictionary::new();
+        decimal.set("S", Object::Name(b"D".to_vec()));
+        let mut tree = Dictionary::new();
+        tree.set(
+            "Nums",
+            Object::Array(vec![
+                0.into(),
+                Object::Dictionary(roman),
+                2.into(),
+                Object::Dictionary(decimal),
+            ]),
+        );
+        doc.catalog_mut().unwrap().set("PageLabels", tree);
+
+        // i, blank, 1, 2 then 4: the blank continues as ii
+        remap_page_labels(&mut doc, &[1, 0, 3, 4, 6]).unwrap();
+        let labels: Vec<(i64, PageLabel)> =
+            number_tree_entries(&doc, doc.catalog().unwrap().get(b"PageLabels").unwrap(), 0)
+                .into_iter()
+                .map(|(start, range)| (start, PageLabel::in_range(range.as_dict().unwrap(), 0)))
+                .collect();
+        let label = |style: &[u8], number| PageLabel {
+            style: Some(style.to_vec()),
+            prefix: None,
+            number,
+        };
+        assert_eq!(
+            labels,
+            vec![
+                (0, label(b"r", 1)),
+                (2, label(b"D", 1)),
+                (4, label(b"D", 4))
+            ]
+        );
+    }
+}
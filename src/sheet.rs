@@ -0,0 +1,1570 @@
+use crate::{
+    args::{Binding, FoldType, LayoutType, PaperSize, POINTS_PER_INCH},
+    error::BookifyError,
+    marks::{collation_mark, crop_marks, win_ansi_encode, Mark, CROP_MARK_OFFSET, SLUG_TEXT_SIZE},
+};
+
+/// Width of the slug, the margin around the trim box holding printer's marks, in points
+pub const SLUG_WIDTH: f32 = 18.0;
+
+/// Position of one page on a physical sheet, in PDF points from the bottom-left corner
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Slot {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    /// Clockwise rotation of the placed page in degrees, a multiple of 90
+    pub rotation: i64,
+    /// Sheet area `[x0, y0, x1, y1]` outside of which the placed page is clipped
+    pub clip: Option<[f32; 4]>,
+}
+
+impl Slot {
+    /// Create slot without rotation
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            rotation: 0,
+            clip: None,
+        }
+    }
+
+    /// Return the same slot clipped to a sheet area
+    pub fn clipped(self, clip: [f32; 4]) -> Self {
+        Self {
+            clip: Some(clip),
+            ..self
+        }
+    }
+
+    /// Return the same slot turned by a clockwise rotation
+    pub fn rotated(self, rotation: i64) -> Self {
+        Self {
+            rotation: (self.rotation + rotation).rem_euclid(360),
+            ..self
+        }
+    }
+
+    /// Computes the transformation matrix that places a page box into the slot.
+    ///
+    /// The page is rotated clockwise by the slot rotation plus `page_rotation` (the page's own
+    /// /Rotate), scaled uniformly to fit the slot and centered in it.
+    ///
+    /// # Parameters
+    /// * `bbox` - Page box `[x0, y0, x1, y1]` in page space
+    /// * `page_rotation` - Clockwise rotation of the page in degrees
+    ///
+    /// # Returns
+    /// `[f32; 6]` - Matrix `[a b c d e f]` suitable for the `cm` operator
+    ///
+    /// # Example
+    /// ```
+    /// use bookify_rs::sheet::Slot;
+    ///
+    /// let slot = Slot::new(100.0, 0.0, 100.0, 200.0).rotated(180);
+    /// let matrix = slot.placement_matrix([0.0, 0.0, 100.0, 200.0], 0);
+    /// assert_eq!(matrix, [-1.0, 0.0, 0.0, -1.0, 200.0, 200.0]);
+    /// ```
+    pub fn placement_matrix(&self, bbox: [f32; 4], page_rotation: i64) -> [f32; 6] {
+        let [x0, y0, x1, y1] = bbox;
+        let (w, h) = (x1 - x0, y1 - y0);
+
+        // Rotate around the box origin, keeping the rotated box in the positive quadrant
+        let rotation = (self.rotation + page_rotation).rem_euclid(360);
+        let ([a, b, c, d, e, f], (rotated_w, rotated_h)) = match rotation {
+            90 => ([0.0, -1.0, 1.0, 0.0, 0.0, w], (h, w)),
+            180 => ([-1.0, 0.0, 0.0, -1.0, w, h], (w, h)),
+            270 => ([0.0, 1.0, -1.0, 0.0, h, 0.0], (h, w)),
+            _ => ([1.0, 0.0, 0.0, 1.0, 0.0, 0.0], (w, h)),
+        };
+        let (e, f) = (e - (a * x0 + c * y0), f - (b * x0 + d * y0));
+
+        // Scale to fit and center in the slot
+        let scale = (self.width / rotated_w).min(self.height / rotated_h);
+        let offset_x = self.x + (self.width - rotated_w * scale) / 2.0;
+        let offset_y = self.y + (self.height - rotated_h * scale) / 2.0;
+
+        [
+            a * scale,
+            b * scale,
+            c * scale,
+            d * scale,
+            e * scale + offset_x,
+            f * scale + offset_y,
+        ]
+    }
+}
+
+/// Apply a placement matrix to a point
+pub fn transform_point([a, b, c, d, e, f]: [f32; 6], x: f32, y: f32) -> (f32, f32) {
+    (a * x + c * y + e, b * x + d * y + f)
+}
+
+/// Geometry of one side of a physical sheet: its size, page slots and printer's marks
+#[derive(Clone, Debug, PartialEq)]
+pub struct SheetLayout {
+    pub width: f32,
+    pub height: f32,
+    /// Slots in imposition order (left-to-right, top-to-bottom)
+    pub slots: Vec<Slot>,
+    pub marks: Vec<Mark>,
+    /// Outermost spine fold, from the top (or left) end to the bottom (or right) end
+    pub spine: Option<((f32, f32), (f32, f32))>,
+}
+
+/// Builds the single-sided mini-zine sheet for pages of the given size.
+///
+/// The sheet holds 4 x 2 pages. The top row is rotated 180°, a cut runs along the
+/// horizontal fold between the two middle columns, and fold marks are placed at the
+/// sheet edges on every fold line.
+///
+/// # Example
+/// ```
+/// use bookify_rs::sheet::mini_zine_sheet;
+///
+/// let sheet = mini_zine_sheet(100.0, 150.0);
+/// assert_eq!((sheet.width, sheet.height), (400.0, 300.0));
+/// assert_eq!(sheet.slots.len(), 8);
+/// assert_eq!(sheet.slots[0].rotation, 180);
+/// assert_eq!(sheet.slots[4].rotation, 0);
+/// ```
+pub fn mini_zine_sheet(page_width: f32, page_height: f32) -> SheetLayout {
+    let width = page_width * 4.0;
+    let height = page_height * 2.0;
+
+    let mut slots = Vec::with_capacity(8);
+    for row in 0..2 {
+        // Rows run top to bottom, the upper row is upside down
+        let y = if row == 0 { page_height } else { 0.0 };
+        for column in 0..4 {
+            let slot = Slot::new(column as f32 * page_width, y, page_width, page_height);
+            slots.push(if row == 0 { slot.rotated(180) } else { slot });
+        }
+    }
+
+    let mut marks = vec![Mark::Cut {
+        from: (page_width, page_height),
+        to: (page_width * 3.0, page_height),
+    }];
+    for column in 1..4 {
+        let x = column as f32 * page_width;
+        marks.push(Mark::Fold {
+            from: (x, 0.0),
+            to: (x, height),
+        });
+    }
+    marks.push(Mark::Fold {
+        from: (0.0, page_height),
+        to: (width, page_height),
+    });
+
+    SheetLayout {
+        width,
+        height,
+        slots,
+        marks,
+        spine: None,
+    }
+}
+
+/// Builds one side of a booklet sheet surrounded by a slug holding printer's marks.
+///
+/// Pages are laid out as the printer would place them for `generate_booklet_imposition`:
+/// side by side for side binding, stacked vertically for top binding, and in a 2x2 grid
+/// for `LayoutType::FourUp`. Crop marks are drawn around the trim box and along the cut
+/// between the two folios of a `FourUp` sheet, and fold marks on the spine.
+/// `LayoutType::MiniZine` has its own sheet, see `mini_zine_sheet`.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::{Binding, LayoutType}, sheet::booklet_sheet};
+///
+/// let sheet = booklet_sheet(LayoutType::TwoUp, Binding::Ltr, 100.0, 150.0);
+/// assert_eq!((sheet.width, sheet.height), (236.0, 186.0));
+/// assert_eq!(sheet.spine, Some(((118.0, 168.0), (118.0, 18.0))));
+/// ```
+pub fn booklet_sheet(
+    layout: LayoutType,
+    binding: Binding,
+    page_width: f32,
+    page_height: f32,
+) -> SheetLayout {
+    if layout == LayoutType::MiniZine {
+        return mini_zine_sheet(page_width, page_height);
+    }
+    let (columns, rows) = booklet_grid(layout, binding);
+    let trim_width = page_width * columns as f32;
+    let trim_height = page_height * rows as f32;
+    let trim = [
+        SLUG_WIDTH,
+        SLUG_WIDTH,
+        SLUG_WIDTH + trim_width,
+        SLUG_WIDTH + trim_height,
+    ];
+    let width = trim_width + 2.0 * SLUG_WIDTH;
+    let height = trim_height + 2.0 * SLUG_WIDTH;
+
+    // Slots run left to right, top to bottom
+    let mut slots = Vec::with_capacity(columns * rows);
+    for row in 0..rows {
+        for column in 0..columns {
+            slots.push(Slot::new(
+                SLUG_WIDTH + column as f32 * page_width,
+                SLUG_WIDTH + (rows - 1 - row) as f32 * page_height,
+                page_width,
+                page_height,
+            ));
+        }
+    }
+
+    // Spine fold runs through the middle of the trim box, the other middle line is a cut
+    let middle_x = SLUG_WIDTH + page_width;
+    let middle_y = SLUG_WIDTH + page_height;
+    let (fold, spine, cuts_x, cuts_y) = if binding == Binding::Top {
+        let fold = Mark::Fold {
+            from: (0.0, middle_y),
+            to: (width, middle_y),
+        };
+        let spine = ((trim[0], middle_y), (trim[0] + page_width, middle_y));
+        let cuts_x = if columns > 1 { vec![middle_x] } else { vec![] };
+        (fold, spine, cuts_x, vec![])
+    } else {
+        let fold = Mark::Fold {
+            from: (middle_x, 0.0),
+            to: (middle_x, height),
+        };
+        let spine = ((middle_x, trim[3]), (middle_x, trim[3] - page_height));
+        let cuts_y = if rows > 1 { vec![middle_y] } else { vec![] };
+        (fold, spine, vec![], cuts_y)
+    };
+
+    let mut marks = crop_marks(trim, &cuts_x, &cuts_y);
+    marks.push(fold);
+
+    SheetLayout {
+        width,
+        height,
+        slots,
+        marks,
+        spine: Some(spine),
+    }
+}
+
+/// Columns and rows of pages on one side of a two-up or four-up booklet sheet
+fn booklet_grid(layout: LayoutType, binding: Binding) -> (usize, usize) {
+    match (layout, binding) {
+        (LayoutType::TwoUp, Binding::Top) => (1, 2),
+        (LayoutType::FourUp, _) => (2, 2),
+        _ => (2, 1),
+    }
+}
+
+/// Builds the page cut out of one slot of an imposed booklet sheet, undoing `booklet_sheet`.
+///
+/// The sheet, less `margin` on every edge, is divided into the slot grid of the layout.
+/// The returned layout has the size of one slot and places the whole sheet, clipped, so
+/// that only that slot shows, turned right side up when it was printed `upside_down`.
+///
+/// # Parameters
+/// * `sheet_width`, `sheet_height` - Size of the imposed sheet side
+/// * `margin` - Border around the slots to cut away, such as a slug or a scanner border
+/// * `slot` - Slot index on the side, left to right, top to bottom
+///
+/// # Errors
+/// Returns an error for the mini-zine layout, or when the margin leaves no room for the slots.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::{Binding, LayoutType}, sheet::booklet_piece};
+///
+/// let piece = booklet_piece(LayoutType::TwoUp, Binding::Ltr, 200.0, 150.0, 0.0, 1, false).unwrap();
+/// assert_eq!((piece.width, piece.height), (100.0, 150.0));
+/// assert_eq!((piece.slots[0].x, piece.slots[0].y), (-100.0, 0.0));
+/// ```
+pub fn booklet_piece(
+    layout: LayoutType,
+    binding: Binding,
+    sheet_width: f32,
+    sheet_height: f32,
+    margin: f32,
+    slot: usize,
+    upside_down: bool,
+) -> Result<SheetLayout, BookifyError> {
+    if layout == LayoutType::MiniZine {
+        return Err(BookifyError::invalid_argument(
+            "Mini-zine sheets cannot be split back into pages",
+        ));
+    }
+    let (columns, rows) = booklet_grid(layout, binding);
+    let width = (sheet_width - 2.0 * margin) / columns as f32;
+    let height = (sheet_height - 2.0 * margin) / rows as f32;
+    if width <= 0.0 || height <= 0.0 {
+        return Err(BookifyError::invalid_argument(format!(
+            "Margin {:.1}pt leaves no room on a {:.1}x{:.1}pt sheet",
+            margin, sheet_width, sheet_height
+        )));
+    }
+
+    // Bottom-left corner of the slot on the sheet, moved to the origin of the piece
+    let x = margin + (slot % columns) as f32 * width;
+    let y = margin + (rows - 1 - slot / columns) as f32 * height;
+    let sheet = if upside_down {
+        Slot::new(
+            x + width - sheet_width,
+            y + height - sheet_height,
+            sheet_width,
+            sheet_height,
+        )
+        .rotated(180)
+    } else {
+        Slot::new(-x, -y, sheet_width, sheet_height)
+    };
+
+    Ok(SheetLayout {
+        width,
+        height,
+        slots: vec![sheet.clipped([0.0, 0.0, width, height])],
+        marks: Vec::new(),
+        spine: None,
+    })
+}
+
+/// Builds every sheet side of a booklet printed with printer's marks.
+///
+/// Each side is a `booklet_sheet` with its sheet and side named in the slug. When the
+/// book has several signatures, the slug also names the signature, and the outside of the
+/// first sheet of each signature carries its collation mark on the spine.
+///
+/// # Parameters
+/// * `layout` - Layout type, `TwoUp` or `FourUp`
+/// * `binding` - Binding edge of the booklet
+/// * `page_width`, `page_height` - Size of a booklet page
+/// * `signatures` - Number of sheets of each signature, see `split_signatures`
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::{Binding, LayoutType}, marks::Mark, sheet::booklet_sides};
+///
+/// let sides = booklet_sides(LayoutType::TwoUp, Binding::Ltr, 100.0, 150.0, &[2, 1]);
+/// assert_eq!(sides.len(), 6);
+/// let bars = |i: usize| sides[i].marks.iter().filter(|m| matches!(m, Mark::Bar { .. })).count();
+/// assert_eq!((bars(0), bars(1), bars(2), bars(4)), (1, 0, 0, 1));
+/// ```
+pub fn booklet_sides(
+    layout: LayoutType,
+    binding: Binding,
+    page_width: f32,
+    page_height: f32,
+    signatures: &[u32],
+) -> Vec<SheetLayout> {
+    let base = booklet_sheet(layout, binding, page_width, page_height);
+    let total_sheets: u32 = signatures.iter().sum();
+    let text_at = (SLUG_WIDTH * 4.0 / 3.0, (SLUG_WIDTH - SLUG_TEXT_SIZE) / 2.0);
+
+    let mut sides = Vec::with_capacity(total_sheets as usize * 2);
+    let mut sheet_number = 0;
+    for (signature_index, &sheets) in signatures.iter().enumerate() {
+        for sheet_in_signature in 0..sheets {
+            sheet_number += 1;
+            for side_name in ["A", "B"] {
+                let mut side = base.clone();
+                let mut slug = format!(
+                    "Sheet {}/{} - Side {}",
+                    sheet_number, total_sheets, side_name
+                );
+                if signatures.len() > 1 {
+                    slug = format!(
+                        "Signature {}/{} - {}",
+                        signature_index + 1,
+                        signatures.len(),
+                        slug
+                    );
+
+                    // Outside of the outermost sheet shows the spine
+                    if let (0, "A", Some(spine)) = (sheet_in_signature, side_name, base.spine) {
+                        side.marks
+                            .push(collation_mark(spine, signature_index, signatures.len()));
+                    }
+                }
+                side.marks.push(Mark::Text {
+                    at: text_at,
+                    text: slug,
+                });
+                sides.push(side);
+            }
+        }
+    }
+    sides
+}
+
+/// Step-and-repeat grid placed on a sheet, lengths in points
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GridSpec {
+    pub columns: u32,
+    pub rows: u32,
+    /// Space between cells
+    pub gutter: f32,
+    /// Minimum space between the grid and the sheet edges
+    pub margin: f32,
+    /// Sheet size, `None` to fit the sheet around the grid
+    pub sheet: Option<PaperSize>,
+    /// Draw crop marks around every cell
+    pub cut_marks: bool,
+}
+
+/// Builds a step-and-repeat sheet whose cells have the given size.
+///
+/// The grid is centered on the sheet, so fronts and mirrored backs register when the
+/// sheet is printed on both sides. Cut marks are drawn in the margin on every cell edge,
+/// which needs a margin of at least 15pt (about 5.3mm) to be fully visible.
+///
+/// # Errors
+/// Returns an error when the grid has no cells, the gutter or margin is negative,
+/// or the grid and its margins do not fit on the requested sheet.
+///
+/// # Example
+/// ```
+/// use bookify_rs::sheet::{grid_sheet, GridSpec};
+///
+/// let spec = GridSpec { columns: 2, rows: 1, gutter: 10.0, margin: 20.0, sheet: None, cut_marks: false };
+/// let sheet = grid_sheet(&spec, 100.0, 50.0).unwrap();
+/// assert_eq!((sheet.width, sheet.height), (250.0, 90.0));
+/// assert_eq!((sheet.slots[1].x, sheet.slots[1].y), (130.0, 20.0));
+/// ```
+pub fn grid_sheet(
+    spec: &GridSpec,
+    cell_width: f32,
+    cell_height: f32,
+) -> Result<SheetLayout, BookifyError> {
+    if spec.columns == 0 || spec.rows == 0 {
+        return Err(BookifyError::invalid_argument(
+            "Grid needs at least one column and one row",
+        ));
+    }
+    if spec.gutter < 0.0 || spec.margin < 0.0 {
+        return Err(BookifyError::invalid_argument(
+            "Grid gutter and margin must not be negative",
+        ));
+    }
+    let grid_width = spec.columns as f32 * cell_width + (spec.columns - 1) as f32 * spec.gutter;
+    let grid_height = spec.rows as f32 * cell_height + (spec.rows - 1) as f32 * spec.gutter;
+
+    let (width, height) = match spec.sheet {
+        Some(sheet) => {
+            if grid_width + 2.0 * spec.margin > sheet.width + 0.01
+                || grid_height + 2.0 * spec.margin > sheet.height + 0.01
+            {
+                return Err(BookifyError::invalid_argument(format!(
+                    "{}x{} grid of {:.1}x{:.1}pt cells does not fit on a {:.1}x{:.1}pt sheet",
+                    spec.columns, spec.rows, cell_width, cell_height, sheet.width, sheet.height
+                )));
+            }
+            (sheet.width, sheet.height)
+        }
+        None => (
+            grid_width + 2.0 * spec.margin,
+            grid_height + 2.0 * spec.margin,
+        ),
+    };
+    let x0 = (width - grid_width) / 2.0;
+    let y0 = (height - grid_height) / 2.0;
+
+    // Slots run left to right, top to bottom
+    let mut slots = Vec::with_capacity((spec.columns * spec.rows) as usize);
+    for row in 0..spec.rows {
+        for column in 0..spec.columns {
+            slots.push(Slot::new(
+                x0 + column as f32 * (cell_width + spec.gutter),
+                y0 + (spec.rows - 1 - row) as f32 * (cell_height + spec.gutter),
+                cell_width,
+                cell_height,
+            ));
+        }
+    }
+
+    let mut marks = Vec::new();
+    if spec.cut_marks {
+        // Every cell edge inside the grid is a cut
+        let mut cuts_x = Vec::new();
+        for column in 1..spec.columns {
+            let edge = x0 + column as f32 * (cell_width + spec.gutter);
+            cuts_x.push(edge - spec.gutter);
+            if spec.gutter > 0.0 {
+                cuts_x.push(edge);
+            }
+        }
+        let mut cuts_y = Vec::new();
+        for row in 1..spec.rows {
+            let edge = y0 + row as f32 * (cell_height + spec.gutter);
+            cuts_y.push(edge - spec.gutter);
+            if spec.gutter > 0.0 {
+                cuts_y.push(edge);
+            }
+        }
+        marks = crop_marks(
+            [x0, y0, x0 + grid_width, y0 + grid_height],
+            &cuts_x,
+            &cuts_y,
+        );
+    }
+
+    Ok(SheetLayout {
+        width,
+        height,
+        slots,
+        marks,
+        spine: None,
+    })
+}
+
+/// Computes the panel widths of the outside of a brochure, left to right.
+///
+/// Panels folding inwards are `tuck` narrower than the others so they close without
+/// buckling: the tuck-in flap of a `TriFold` (outside left) and both gates of a
+/// `GateFold`. `ZFold` panels are all equal. The inside of the sheet uses the same widths
+/// in reverse order.
+///
+/// # Errors
+/// Returns an error when the tuck leaves no room for a panel.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::FoldType, sheet::panel_widths};
+///
+/// assert_eq!(panel_widths(FoldType::TriFold, 300.0, 6.0).unwrap(), vec![94.0, 103.0, 103.0]);
+/// assert_eq!(panel_widths(FoldType::GateFold, 400.0, 4.0).unwrap(), vec![96.0, 104.0, 104.0, 96.0]);
+/// ```
+pub fn panel_widths(fold: FoldType, width: f32, tuck: f32) -> Result<Vec<f32>, BookifyError> {
+    let equal = width / fold.panels_per_side() as f32;
+    if tuck < 0.0 || tuck >= equal {
+        return Err(BookifyError::invalid_argument(format!(
+            "Tuck of {:.1}pt does not fit on {:.1}pt panels",
+            tuck, equal
+        )));
+    }
+
+    let widths = match fold {
+        FoldType::TriFold => {
+            let flap = equal - tuck;
+            let panel = (width - flap) / 2.0;
+            vec![flap, panel, panel]
+        }
+        FoldType::ZFold => vec![equal; 3],
+        FoldType::GateFold => {
+            let gate = equal - tuck;
+            let center = (width - 2.0 * gate) / 2.0;
+            vec![gate, center, center, gate]
+        }
+    };
+    Ok(widths)
+}
+
+/// Builds the outside and inside of a brochure sheet.
+///
+/// Slots follow `panel_widths` and run left to right on both sides, the inside being
+/// laid out for a sheet flipped on its short edge. Fold marks are drawn at the top and
+/// bottom sheet edges on every panel boundary when requested.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::FoldType, sheet::brochure_sides};
+///
+/// let [outside, inside] = brochure_sides(FoldType::TriFold, 300.0, 200.0, 6.0, true).unwrap();
+/// assert_eq!(outside.slots[0].width, 94.0);
+/// assert_eq!((inside.slots[2].x, inside.slots[2].width), (206.0, 94.0));
+/// assert_eq!(outside.marks.len(), 2);
+/// ```
+pub fn brochure_sides(
+    fold: FoldType,
+    width: f32,
+    height: f32,
+    tuck: f32,
+    fold_marks: bool,
+) -> Result<[SheetLayout; 2], BookifyError> {
+    let outside_widths = panel_widths(fold, width, tuck)?;
+    let inside_widths: Vec<f32> = outside_widths.iter().rev().copied().collect();
+
+    let side = |widths: &[f32]| {
+        let mut slots = Vec::with_capacity(widths.len());
+        let mut marks = Vec::new();
+        let mut x = 0.0;
+        for (index, &panel_width) in widths.iter().enumerate() {
+            if index > 0 && fold_marks {
+                marks.push(Mark::Fold {
+                    from: (x, 0.0),
+                    to: (x, height),
+                });
+            }
+            slots.push(Slot::new(x, 0.0, panel_width, height));
+            x += panel_width;
+        }
+        SheetLayout {
+            width,
+            height,
+            slots,
+            marks,
+            spine: None,
+        }
+    };
+
+    Ok([side(&outside_widths), side(&inside_widths)])
+}
+
+/// Largest font size of spine text, in points
+const SPINE_TEXT_MAX_SIZE: f32 = 12.0;
+
+/// Thickness of the paper of a book block
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Caliper {
+    /// Pages per inch, as given by paper mills (a leaf counts as 2 pages)
+    PagesPerInch(f32),
+    /// Thickness of one leaf, in points
+    SheetThickness(f32),
+}
+
+/// Computes the spine width of a book block, in points.
+///
+/// # Example
+/// ```
+/// use bookify_rs::sheet::{spine_width, Caliper};
+///
+/// // 288 pages at 576 PPI make a half-inch spine
+/// assert_eq!(spine_width(288, Caliper::PagesPerInch(576.0)), 36.0);
+/// // 23 pages are printed on 12 leaves
+/// assert_eq!(spine_width(23, Caliper::SheetThickness(0.25)), 3.0);
+/// ```
+pub fn spine_width(total_pages: u32, caliper: Caliper) -> f32 {
+    match caliper {
+        Caliper::PagesPerInch(ppi) => total_pages as f32 / ppi * POINTS_PER_INCH,
+        Caliper::SheetThickness(thickness) => total_pages.div_ceil(2) as f32 * thickness,
+    }
+}
+
+/// Wrap-around cover of a perfect-bound book, lengths in points
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoverSpec {
+    /// Trim size of the book
+    pub page_width: f32,
+    pub page_height: f32,
+    pub spine_width: f32,
+    /// Binding edge, the front cover is on the right of the spine for `Ltr`
+    pub binding: Binding,
+    /// Title printed along the spine, top to bottom, Western European characters only
+    pub spine_text: Option<String>,
+    /// Surround the spread with a slug holding crop marks and spine fold marks
+    pub marks: bool,
+}
+
+/// Builds the cover spread of a perfect-bound book: back cover, spine and front cover.
+///
+/// Slots are in art order: front cover, back cover, spine. The spine text is centered on
+/// the spine, with a size fitting its width, measured with an average Helvetica glyph
+/// width.
+///
+/// # Errors
+/// Returns an error for top binding, which has no wrap-around cover, or when the mark
+/// font cannot print the spine text.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::Binding, sheet::{cover_spread, CoverSpec}};
+///
+/// let spec = CoverSpec {
+///     page_width: 100.0,
+///     page_height: 150.0,
+///     spine_width: 10.0,
+///     binding: Binding::Ltr,
+///     spine_text: None,
+///     marks: false,
+/// };
+/// let spread = cover_spread(&spec).unwrap();
+/// assert_eq!((spread.width, spread.height), (210.0, 150.0));
+/// let origins: Vec<f32> = spread.slots.iter().map(|slot| slot.x).collect();
+/// assert_eq!(origins, vec![110.0, 0.0, 100.0]);
+/// ```
+pub fn cover_spread(spec: &CoverSpec) -> Result<SheetLayout, BookifyError> {
+    if spec.binding == Binding::Top {
+        return Err(BookifyError::invalid_argument(
+            "Top binding has no wrap-around cover",
+        ));
+    }
+
+    let slug = if spec.marks { SLUG_WIDTH } else { 0.0 };
+    let trim_width = 2.0 * spec.page_width + spec.spine_width;
+    let width = trim_width + 2.0 * slug;
+    let height = spec.page_height + 2.0 * slug;
+
+    // Panels left to right, reversed for right-to-left books
+    let spine_x = slug + spec.page_width;
+    let (front_x, back_x) = match spec.binding {
+        Binding::Rtl => (slug, spine_x + spec.spine_width),
+        _ => (spine_x + spec.spine_width, slug),
+    };
+    let slots = vec![
+        Slot::new(front_x, slug, spec.page_width, spec.page_height),
+        Slot::new(back_x, slug, spec.page_width, spec.page_height),
+        Slot::new(spine_x, slug, spec.spine_width, spec.page_height),
+    ];
+
+    let mut marks = Vec::new();
+    if spec.marks {
+        marks = crop_marks(
+            [slug, slug, slug + trim_width, slug + spec.page_height],
+            &[],
+            &[],
+        );
+        for x in [spine_x, spine_x + spec.spine_width] {
+            marks.push(Mark::Fold {
+                from: (x, 0.0),
+                to: (x, height),
+            });
+        }
+    }
+    if let Some(text) = &spec.spine_text {
+        win_ansi_encode(text)?;
+        let size = (spec.spine_width * 0.6).min(SPINE_TEXT_MAX_SIZE);
+        let length = text.chars().count() as f32 * size * 0.5;
+        marks.push(Mark::VerticalText {
+            at: (
+                spine_x + spec.spine_width / 2.0 - size * 0.35,
+                slug + (spec.page_height + length) / 2.0,
+            ),
+            size,
+            text: text.clone(),
+        });
+    }
+
+    Ok(SheetLayout {
+        width,
+        height,
+        slots,
+        marks,
+        spine: None,
+    })
+}
+
+/// Space between handout cells, in points
+const HANDOUT_GUTTER: f32 = 12.0;
+
+/// Height reserved under each handout slide for its number, in points
+const HANDOUT_LABEL_HEIGHT: f32 = 10.0;
+
+/// Distance between two ruled note lines, in points
+const NOTE_LINE_SPACING: f32 = 18.0;
+
+/// Lecture handout: slides in reading order with optional ruled notes, lengths in points
+#[derive(Clone, Debug, PartialEq)]
+pub struct HandoutSpec {
+    /// Slides per page, one of 2, 3, 4, 6 or 9
+    pub slides: u32,
+    pub sheet: PaperSize,
+    /// Space between the content and the sheet edges, the header is printed in the top one
+    pub margin: f32,
+    /// Draw ruled lines for notes beside each slide
+    pub notes: bool,
+    /// Text printed in the page header, Western European characters only
+    pub header: String,
+}
+
+/// Builds the pages of a handout holding `total_slides` slides of the given size.
+///
+/// Slides fill a grid left to right, top to bottom: 1x2, 1x3, 2x2, 2x3 or 3x3 cells.
+/// Each slide is scaled to fit its cell, framed and numbered underneath. With notes, a
+/// slide takes the left half of its cell and ruled lines fill the right half. The header
+/// names the handout and the page, or only the page when the header text is empty.
+///
+/// # Errors
+/// Returns an error for an unsupported number of slides per page, when the margin is
+/// negative or leaves no room for the slides, or when the mark font cannot print the header.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::PaperSize, sheet::{handout_sides, HandoutSpec}};
+///
+/// let spec = HandoutSpec {
+///     slides: 4,
+///     sheet: "a4".parse().unwrap(),
+///     margin: 30.0,
+///     notes: false,
+///     header: "Lecture 1".to_string(),
+/// };
+/// let pages = handout_sides(&spec, 320.0, 240.0, 6).unwrap();
+/// assert_eq!(pages.len(), 2);
+/// assert_eq!(pages[1].slots.len(), 4);
+/// ```
+pub fn handout_sides(
+    spec: &HandoutSpec,
+    slide_width: f32,
+    slide_height: f32,
+    total_slides: u32,
+) -> Result<Vec<SheetLayout>, BookifyError> {
+    let (columns, rows) = match spec.slides {
+        2 => (1, 2),
+        3 => (1, 3),
+        4 => (2, 2),
+        6 => (2, 3),
+        9 => (3, 3),
+        slides => {
+            return Err(BookifyError::invalid_argument(format!(
+                "Handouts hold 2, 3, 4, 6 or 9 slides per page, not {}",
+                slides
+            )))
+        }
+    };
+    if spec.margin < 0.0 {
+        return Err(BookifyError::invalid_argument(
+            "Handout margin must not be negative",
+        ));
+    }
+    win_ansi_encode(&spec.header)?;
+    let (width, height) = (spec.sheet.width, spec.sheet.height);
+    let cell_width =
+        (width - 2.0 * spec.margin - (columns - 1) as f32 * HANDOUT_GUTTER) / columns as f32;
+    let cell_height =
+        (height - 2.0 * spec.margin - (rows - 1) as f32 * HANDOUT_GUTTER) / rows as f32;
+    let slide_area = (
+        if spec.notes {
+            cell_width / 2.0
+        } else {
+            cell_width
+        },
+        cell_height - HANDOUT_LABEL_HEIGHT,
+    );
+    if slide_area.0 <= 0.0 || slide_area.1 <= 0.0 {
+        return Err(BookifyError::invalid_argument(format!(
+            "Margin of {:.1}pt leaves no room for {} slides on a {:.1}x{:.1}pt sheet",
+            spec.margin, spec.slides, width, height
+        )));
+    }
+
+    // Slides are top-aligned, centered in their cell without notes
+    let scale = (slide_area.0 / slide_width).min(slide_area.1 / slide_height);
+    let (fitted_width, fitted_height) = (slide_width * scale, slide_height * scale);
+    let pages = total_slides.div_ceil(spec.slides).max(1);
+
+    let mut sides = Vec::with_capacity(pages as usize);
+    for page in 0..pages {
+        let mut slots = Vec::with_capacity(spec.slides as usize);
+        let mut text = format!("Page {}/{}", page + 1, pages);
+        if !spec.header.is_empty() {
+            text = format!("{} - {}", spec.header, text);
+        }
+        let mut marks = vec![Mark::Text {
+            at: (spec.margin, height - (spec.margin + SLUG_TEXT_SIZE) / 2.0),
+            text,
+        }];
+
+        for row in 0..rows {
+            for column in 0..columns {
+                let number = page * spec.slides + row * columns + column + 1;
+                let cell_x = spec.margin + column as f32 * (cell_width + HANDOUT_GUTTER);
+                let cell_top = height - spec.margin - row as f32 * (cell_height + HANDOUT_GUTTER);
+                let x = if spec.notes {
+                    cell_x
+                } else {
+                    cell_x + (cell_width - fitted_width) / 2.0
+                };
+                let slot = Slot::new(x, cell_top - fitted_height, fitted_width, fitted_height);
+                slots.push(slot);
+                if number > total_slides {
+                    continue;
+                }
+
+                let (x0, y0, x1, y1) = (slot.x, slot.y, slot.x + slot.width, cell_top);
+                marks.extend([
+                    Mark::Line {
+                        from: (x0, y0),
+                        to: (x1, y0),
+                    },
+                    Mark::Line {
+                        from: (x1, y0),
+                        to: (x1, y1),
+                    },
+                    Mark::Line {
+                        from: (x1, y1),
+                        to: (x0, y1),
+                    },
+                    Mark::Line {
+                        from: (x0, y1),
+                        to: (x0, y0),
+                    },
+                ]);
+                marks.push(Mark::Text {
+                    at: (x0, y0 - HANDOUT_LABEL_HEIGHT + 2.0),
+                    text: format!("Slide {}", number),
+                });
+
+                if spec.notes {
+                    let notes_x0 = cell_x + cell_width / 2.0 + HANDOUT_GUTTER;
+                    let notes_x1 = cell_x + cell_width;
+                    let mut line_y = cell_top - NOTE_LINE_SPACING;
+                    while line_y >= cell_top - cell_height {
+                        marks.push(Mark::Line {
+                            from: (notes_x0, line_y),
+                            to: (notes_x1, line_y),
+                        });
+                        line_y -= NOTE_LINE_SPACING;
+                    }
+                }
+            }
+        }
+
+        sides.push(SheetLayout {
+            width,
+            height,
+            slots,
+            marks,
+            spine: None,
+        });
+    }
+    Ok(sides)
+}
+
+/// Poster tiled across several sheets, lengths in points
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PosterSpec {
+    /// Sheet size, turned to landscape when that needs fewer tiles
+    pub sheet: PaperSize,
+    /// Space between the printable area and the sheet edges, holding the marks
+    pub margin: f32,
+    /// Width of the strip shared by neighbouring tiles, used to glue them together
+    pub overlap: f32,
+    /// Enlargement factor of the poster page
+    pub scale: f32,
+    /// Add an overview page showing how the tiles are assembled
+    pub overview: bool,
+}
+
+/// Name of a tile as printed on the sheets: row letter(s) followed by the column number
+///
+/// # Example
+/// ```
+/// use bookify_rs::sheet::tile_name;
+///
+/// assert_eq!(tile_name(0, 0), "A1");
+/// assert_eq!(tile_name(27, 3), "AB4");
+/// ```
+pub fn tile_name(row: u32, column: u32) -> String {
+    let mut letters = Vec::new();
+    let mut index = row + 1;
+    while index > 0 {
+        index -= 1;
+        letters.push(b'A' + (index % 26) as u8);
+        index /= 26;
+    }
+    letters.reverse();
+    format!(
+        "{}{}",
+        String::from_utf8(letters).unwrap_or_default(),
+        column + 1
+    )
+}
+
+/// Number of tiles needed to cover a length, each tile showing `area` with `overlap` shared
+fn tile_count(length: f32, area: f32, overlap: f32) -> u32 {
+    if length <= area + 0.01 {
+        1
+    } else {
+        ((length - overlap) / (area - overlap) - 0.001).ceil() as u32
+    }
+}
+
+/// Builds the sheets of a poster page enlarged and tiled across several sheets.
+///
+/// Tiles run left to right, top to bottom, starting at the top-left corner of the poster.
+/// Each tile prints the poster through its printable area and repeats the last `overlap`
+/// of its left and upper neighbours. Crop marks frame the printable area, extra ticks
+/// show the glue strips on the right and bottom edges, and the slug names the tile and
+/// the neighbours it is glued onto. The optional overview comes first, with the poster
+/// scaled to fit the sheet and the tile grid drawn over it.
+///
+/// # Parameters
+/// * `spec` - Poster specification
+/// * `page_width`, `page_height` - Size of the poster page, before scaling
+///
+/// # Errors
+/// Returns an error when the scale is not positive, or when the margins leave no printable
+/// area or the overlap fills it.
+///
+/// # Example
+/// ```
+/// use bookify_rs::{args::PaperSize, sheet::{poster_sheets, PosterSpec}};
+///
+/// let sheet = PaperSize { width: 100.0, height: 200.0 };
+/// let spec = PosterSpec { sheet, margin: 10.0, overlap: 20.0, scale: 1.0, overview: false };
+/// let tiles = poster_sheets(&spec, 300.0, 100.0).unwrap();
+/// // Landscape sheets show 180x80pt: 2 columns and 2 rows
+/// assert_eq!(tiles.len(), 4);
+/// assert_eq!((tiles[1].slots[0].x, tiles[1].slots[0].y), (-150.0, -10.0));
+/// ```
+pub fn poster_sheets(
+    spec: &PosterSpec,
+    page_width: f32,
+    page_height: f32,
+) -> Result<Vec<SheetLayout>, BookifyError> {
+    if spec.scale <= 0.0 || spec.overlap < 0.0 || spec.margin < 0.0 {
+        return Err(BookifyError::invalid_argument(
+            "Poster scale must be positive, overlap and margin must not be negative",
+        ));
+    }
+    let (poster_width, poster_height) = (page_width * spec.scale, page_height * spec.scale);
+
+    // Pick the sheet orientation needing fewer tiles
+    let mut candidates = Vec::with_capacity(2);
+    for sheet in [spec.sheet, spec.sheet.landscape()] {
+        let area = (
+            sheet.width - 2.0 * spec.margin,
+            sheet.height - 2.0 * spec.margin,
+        );
+        if area.0 <= spec.overlap || area.1 <= spec.overlap {
+            continue;
+        }
+        let columns = tile_count(poster_width, area.0, spec.overlap);
+        let rows = tile_count(poster_height, area.1, spec.overlap);
+        candidates.push((columns * rows, sheet, area, columns, rows));
+    }
+    let (_, sheet, (area_width, area_height), columns, rows) = candidates
+        .into_iter()
+        .min_by_key(|candidate| candidate.0)
+        .ok_or_else(|| {
+            BookifyError::invalid_argument(format!(
+                "Margin of {:.1}pt and overlap of {:.1}pt leave no room on a {:.1}x{:.1}pt sheet",
+                spec.margin, spec.overlap, spec.sheet.width, spec.sheet.height
+            ))
+        })?;
+    let step_x = area_width - spec.overlap;
+    let step_y = area_height - spec.overlap;
+    let area = [
+        spec.margin,
+        spec.margin,
+        spec.margin + area_width,
+        spec.margin + area_height,
+    ];
+    let text_at = (
+        spec.margin + CROP_MARK_OFFSET,
+        (spec.margin - SLUG_TEXT_SIZE) / 2.0,
+    );
+
+    let mut sheets = Vec::with_capacity((columns * rows) as usize + 1);
+    if spec.overview {
+        sheets.push(poster_overview(
+            sheet,
+            area,
+            (poster_width, poster_height),
+            (step_x, step_y),
+            (columns, rows),
+            text_at,
+        ));
+    }
+
+    for row in 0..rows {
+        for column in 0..columns {
+            // Whole poster shifted so this tile's part lands on the printable area
+            let slot = Slot::new(
+                spec.margin - column as f32 * step_x,
+                area[3] - poster_height + row as f32 * step_y,
+                poster_width,
+                poster_height,
+            )
+            .clipped(area);
+
+            // Right and bottom strips are covered by the next tiles
+            let cuts_x = if column + 1 < columns {
+                vec![area[2] - spec.overlap]
+            } else {
+                vec![]
+            };
+            let cuts_y = if row + 1 < rows {
+                vec![area[1] + spec.overlap]
+            } else {
+                vec![]
+            };
+            let mut marks = crop_marks(area, &cuts_x, &cuts_y);
+
+            let mut slug = format!(
+                "Tile {} - Row {}/{} - Column {}/{}",
+                tile_name(row, column),
+                row + 1,
+                rows,
+                column + 1,
+                columns
+            );
+            if column > 0 {
+                slug += &format!(" - Left edge onto {}", tile_name(row, column - 1));
+            }
+            if row > 0 {
+                slug += &format!(" - Top edge onto {}", tile_name(row - 1, column));
+            }
+            marks.push(Mark::Text {
+                at: text_at,
+                text: slug,
+            });
+
+            sheets.push(SheetLayout {
+                width: sheet.width,
+                height: sheet.height,
+                slots: vec![slot],
+                marks,
+                spine: None,
+            });
+        }
+    }
+    Ok(sheets)
+}
+
+/// Builds the overview sheet of a poster: the poster fitted to the printable area with
+/// every tile outlined and named
+fn poster_overview(
+    sheet: PaperSize,
+    area: [f32; 4],
+    poster: (f32, f32),
+    step: (f32, f32),
+    tiles: (u32, u32),
+    text_at: (f32, f32),
+) -> SheetLayout {
+    let slot = Slot::new(area[0], area[1], area[2] - area[0], area[3] - area[1]);
+    let scale = (slot.width / poster.0).min(slot.height / poster.1);
+    let (width, height) = (poster.0 * scale, poster.1 * scale);
+    let x0 = slot.x + (slot.width - width) / 2.0;
+    let y1 = slot.y + (slot.height + height) / 2.0;
+    let (columns, rows) = tiles;
+
+    // Each tile shows its part up to the next tile, which is glued over it
+    let mut edges_x: Vec<f32> = (0..columns)
+        .map(|column| x0 + column as f32 * step.0 * scale)
+        .collect();
+    edges_x.push(x0 + width);
+    let mut edges_y: Vec<f32> = (0..rows)
+        .map(|row| y1 - row as f32 * step.1 * scale)
+        .collect();
+    edges_y.push(y1 - height);
+
+    let mut marks = Vec::new();
+    for &x in &edges_x {
+        marks.push(Mark::Cut {
+            from: (x, y1 - height),
+            to: (x, y1),
+        });
+    }
+    for &y in &edges_y {
+        marks.push(Mark::Cut {
+            from: (x0, y),
+            to: (x0 + width, y),
+        });
+    }
+    for row in 0..rows {
+        for column in 0..columns {
+            marks.push(Mark::Text {
+                at: (
+                    edges_x[column as usize] + 2.0,
+                    edges_y[row as usize] - SLUG_TEXT_SIZE - 2.0,
+                ),
+                text: tile_name(row, column),
+            });
+        }
+    }
+    marks.push(Mark::Text {
+        at: text_at,
+        text: format!("Overview - {} rows x {} columns", rows, columns),
+    });
+
+    SheetLayout {
+        width: sheet.width,
+        height: sheet.height,
+        slots: vec![slot],
+        marks,
+        spine: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placement_identity() {
+        let slot = Slot::new(0.0, 0.0, 100.0, 200.0);
+        assert_eq!(
+            slot.placement_matrix([0.0, 0.0, 100.0, 200.0], 0),
+            [1.0, 0.0, 0.0, 1.0, 0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn test_placement_scaled_and_centered() {
+        // 100x100 box into a 200x400 slot: scale 2, centered vertically
+        let slot = Slot::new(10.0, 20.0, 200.0, 400.0);
+        assert_eq!(
+            slot.placement_matrix([0.0, 0.0, 100.0, 100.0], 0),
+            [2.0, 0.0, 0.0, 2.0, 10.0, 120.0]
+        );
+    }
+
+    #[test]
+    fn test_placement_page_rotation() {
+        // Landscape page turned by its own /Rotate 90 fills a portrait slot
+        let slot = Slot::new(0.0, 0.0, 100.0, 200.0);
+        assert_eq!(
+            slot.placement_matrix([0.0, 0.0, 200.0, 100.0], 90),
+            [0.0, -1.0, 1.0, 0.0, 0.0, 200.0]
+        );
+    }
+
+    #[test]
+    fn test_placement_offset_box() {
+        // Box origin is moved to the slot origin
+        let slot = Slot::new(0.0, 0.0, 100.0, 100.0);
+        assert_eq!(
+            slot.placement_matrix([50.0, 50.0, 150.0, 150.0], 0),
+            [1.0, 0.0, 0.0, 1.0, -50.0, -50.0]
+        );
+    }
+
+    #[test]
+    fn test_booklet_sheet_four_up() {
+        let sheet = booklet_sheet(LayoutType::FourUp, Binding::Ltr, 100.0, 150.0);
+        assert_eq!((sheet.width, sheet.height), (236.0, 336.0));
+        let origins: Vec<(f32, f32)> = sheet.slots.iter().map(|s| (s.x, s.y)).collect();
+        assert_eq!(
+            origins,
+            vec![(18.0, 168.0), (118.0, 168.0), (18.0, 18.0), (118.0, 18.0)]
+        );
+        // Spine of the outer folio is the fold of the top row
+        assert_eq!(sheet.spine, Some(((118.0, 318.0), (118.0, 168.0))));
+    }
+
+    #[test]
+    fn test_booklet_sheet_top_binding() {
+        let sheet = booklet_sheet(LayoutType::TwoUp, Binding::Top, 150.0, 100.0);
+        assert_eq!((sheet.width, sheet.height), (186.0, 236.0));
+        let origins: Vec<(f32, f32)> = sheet.slots.iter().map(|s| (s.x, s.y)).collect();
+        assert_eq!(origins, vec![(18.0, 118.0), (18.0, 18.0)]);
+        assert_eq!(sheet.spine, Some(((18.0, 118.0), (168.0, 118.0))));
+        assert!(sheet.marks.contains(&Mark::Fold {
+            from: (0.0, 118.0),
+            to: (186.0, 118.0)
+        }));
+    }
+
+    #[test]
+    fn test_booklet_piece_upside_down() {
+        // Top binding stacks two 100x50pt pages on a 100x100pt sheet inside a 10pt margin
+        let piece =
+            booklet_piece(LayoutType::TwoUp, Binding::Top, 120.0, 120.0, 10.0, 0, true).unwrap();
+        assert_eq!((piece.width, piece.height), (100.0, 50.0));
+        let slot = piece.slots[0];
+        assert_eq!((slot.x, slot.y, slot.rotation), (-10.0, -10.0, 180));
+        // The top slot of the sheet lands, turned, on the piece
+        let matrix = slot.placement_matrix([0.0, 0.0, 120.0, 120.0], 0);
+        assert_eq!(matrix, [-1.0, 0.0, 0.0, -1.0, 110.0, 110.0]);
+        assert!(
+            booklet_piece(LayoutType::FourUp, Binding::Ltr, 20.0, 20.0, 10.0, 0, false).is_err()
+        );
+    }
+
+    #[test]
+    fn test_booklet_sides_slug() {
+        let sides = booklet_sides(LayoutType::FourUp, Binding::Ltr, 100.0, 150.0, &[1, 1]);
+        let slug = |side: &SheetLayout| {
+            side.marks.iter().find_map(|mark| match mark {
+                Mark::Text { text, .. } => Some(text.clone()),
+                _ => None,
+            })
+        };
+        assert_eq!(
+            slug(&sides[3]).unwrap(),
+            "Signature 2/2 - Sheet 2/2 - Side B"
+        );
+
+        // Single signature omits signature number and collation marks
+        let sides = booklet_sides(LayoutType::FourUp, Binding::Ltr, 100.0, 150.0, &[2]);
+        assert_eq!(slug(&sides[0]).unwrap(), "Sheet 1/2 - Side A");
+        assert!(sides
+            .iter()
+            .all(|side| !side.marks.iter().any(|m| matches!(m, Mark::Bar { .. }))));
+    }
+
+    #[test]
+    fn test_grid_sheet_centered_on_paper() {
+        let spec = GridSpec {
+            columns: 2,
+            rows: 2,
+            gutter: 0.0,
+            margin: 10.0,
+            sheet: Some(PaperSize {
+                width: 300.0,
+                height: 300.0,
+            }),
+            cut_marks: true,
+        };
+        let sheet = grid_sheet(&spec, 100.0, 50.0).unwrap();
+        let origins: Vec<(f32, f32)> = sheet.slots.iter().map(|s| (s.x, s.y)).collect();
+        assert_eq!(
+            origins,
+            vec![(50.0, 150.0), (150.0, 150.0), (50.0, 100.0), (150.0, 100.0)]
+        );
+        // 3 vertical and 3 horizontal lines, ticks on both ends
+        assert_eq!(sheet.marks.len(), 12);
+    }
+
+    #[test]
+    fn test_grid_sheet_too_small() {
+        let spec = GridSpec {
+            columns: 3,
+            rows: 1,
+            gutter: 0.0,
+            margin: 10.0,
+            sheet: Some(PaperSize {
+                width: 300.0,
+                height: 300.0,
+            }),
+            cut_marks: false,
+        };
+        assert!(grid_sheet(&spec, 100.0, 50.0).is_err());
+    }
+
+    #[test]
+    fn test_grid_sheet_rejects_invalid_spec() {
+        let spec = GridSpec {
+            columns: 2,
+            rows: 2,
+            gutter: 0.0,
+            margin: 10.0,
+            sheet: None,
+            cut_marks: false,
+        };
+        for spec in [
+            GridSpec { columns: 0, ..spec },
+            GridSpec { rows: 0, ..spec },
+            GridSpec {
+                gutter: -5.0,
+                ..spec
+            },
+            GridSpec {
+                margin: -1.0,
+                ..spec
+            },
+        ] {
+            assert!(grid_sheet(&spec, 100.0, 50.0).is_err());
+        }
+    }
+
+    #[test]
+    fn test_cover_spread_rtl_with_marks() {
+        let spec = CoverSpec {
+            page_width: 100.0,
+            page_height: 150.0,
+            spine_width: 20.0,
+            binding: Binding::Rtl,
+            spine_text: Some("Title".to_string()),
+            marks: true,
+        };
+        let spread = cover_spread(&spec).unwrap();
+        assert_eq!((spread.width, spread.height), (256.0, 186.0));
+        // Front cover on the left of the spine
+        let origins: Vec<f32> = spread.slots.iter().map(|slot| slot.x).collect();
+        assert_eq!(origins, vec![18.0, 138.0, 118.0]);
+        assert!(spread.marks.contains(&Mark::VerticalText {
+            at: (123.8, 108.0),
+            size: 12.0,
+            text: "Title".to_string(),
+        }));
+    }
+
+    #[test]
+    fn test_cover_spine_text_encoding() {
+        let spec = CoverSpec {
+            page_width: 100.0,
+            page_height: 150.0,
+            spine_width: 20.0,
+            binding: Binding::Ltr,
+            spine_text: Some("Titel – Zoë".to_string()),
+            marks: false,
+        };
+        assert!(cover_spread(&spec).is_ok());
+        let spec = CoverSpec {
+            spine_text: Some("书名".to_string()),
+            ..spec
+        };
+        assert!(cover_spread(&spec).is_err());
+    }
+
+    #[test]
+    fn test_handout_notes_beside_slides() {
+        let spec = HandoutSpec {
+            slides: 3,
+            sheet: PaperSize {
+                width: 400.0,
+                height: 400.0,
+            },
+            margin: 20.0,
+            notes: true,
+            header: "Deck".to_string(),
+        };
+        let sides = handout_sides(&spec, 160.0, 90.0, 3).unwrap();
+        assert_eq!(sides.len(), 1);
+        // Slides take the left half of 360pt wide cells
+        assert_eq!(sides[0].slots[0], Slot::new(20.0, 278.75, 180.0, 101.25));
+        let lines = |side: &SheetLayout| {
+            side.marks
+                .iter()
+                .filter(|mark| matches!(mark, Mark::Line { .. }))
+                .count()
+        };
+        // 4 frame lines and 6 note lines per slide
+        assert_eq!(lines(&sides[0]), 3 * (4 + 6));
+    }
+
+    #[test]
+    fn test_handout_rejects_invalid_spec() {
+        let spec = HandoutSpec {
+            slides: 5,
+            sheet: PaperSize {
+                width: 400.0,
+                height: 400.0,
+            },
+            margin: 20.0,
+            notes: false,
+            header: String::new(),
+        };
+        assert!(handout_sides(&spec, 160.0, 90.0, 3).is_err());
+        let spec = HandoutSpec {
+            slides: 3,
+            margin: -50.0,
+            ..spec
+        };
+        assert!(handout_sides(&spec, 160.0, 90.0, 3).is_err());
+    }
+
+    #[test]
+    fn test_handout_header_text() {
+        let spec = HandoutSpec {
+            slides: 3,
+            sheet: PaperSize {
+                width: 400.0,
+                height: 400.0,
+            },
+            margin: 20.0,
+            notes: false,
+            header: "Vorlesung Übung".to_string(),
+        };
+        let header = |pages: &[SheetLayout]| match &pages[0].marks[0] {
+            Mark::Text { text, .. } => text.clone(),
+            mark => panic!("unexpected mark {:?}", mark),
+        };
+        let pages = handout_sides(&spec, 160.0, 90.0, 3).unwrap();
+        assert_eq!(header(&pages), "Vorlesung Übung - Page 1/1");
+
+        let untitled = HandoutSpec {
+            header: String::new(),
+            ..spec.clone()
+        };
+        let pages = handout_sides(&untitled, 160.0, 90.0, 3).unwrap();
+        assert_eq!(header(&pages), "Page 1/1");
+
+        // CJK needs a font the marks do not embed
+        let chinese = HandoutSpec {
+            header: "第一讲".to_string(),
+            ..spec
+        };
+        assert!(handout_sides(&chinese, 160.0, 90.0, 3).is_err());
+    }
+
+    #[test]
+    fn test_tile_count() {
+        assert_eq!(tile_count(100.0, 100.0, 10.0), 1);
+        assert_eq!(tile_count(190.0, 100.0, 10.0), 2);
+        assert_eq!(tile_count(191.0, 100.0, 10.0), 3);
+    }
+
+    #[test]
+    fn test_poster_sheets_with_overview() {
+        let spec = PosterSpec {
+            sheet: PaperSize {
+                width: 120.0,
+                height: 120.0,
+            },
+            margin: 10.0,
+            overlap: 10.0,
+            scale: 2.0,
+            overview: true,
+        };
+        let sheets = poster_sheets(&spec, 95.0, 50.0).unwrap();
+        // 190x100pt poster on 100x100pt areas: 2 columns, 1 row, plus the overview
+        assert_eq!(sheets.len(), 3);
+        assert_eq!(sheets[0].slots[0].clip, None);
+        let tile = &sheets[2];
+        assert_eq!(
+            tile.slots[0],
+            Slot::new(-80.0, 10.0, 190.0, 100.0).clipped([10.0, 10.0, 110.0, 110.0])
+        );
+        let slug = tile.marks.iter().find_map(|mark| match mark {
+            Mark::Text { text, .. } => Some(text.as_str()),
+            _ => None,
+        });
+        assert_eq!(
+            slug,
+            Some("Tile A2 - Row 1/1 - Column 2/2 - Left edge onto A1")
+        );
+    }
+
+    #[test]
+    fn test_poster_overlap_fills_sheet() {
+        let spec = PosterSpec {
+            sheet: PaperSize {
+                width: 100.0,
+                height: 100.0,
+            },
+            margin: 20.0,
+            overlap: 60.0,
+            scale: 1.0,
+            overview: false,
+        };
+        assert!(poster_sheets(&spec, 500.0, 500.0).is_err());
+    }
+
+    #[test]
+    fn test_poster_negative_margin() {
+        let spec = PosterSpec {
+            sheet: PaperSize {
+                width: 100.0,
+                height: 100.0,
+            },
+            margin: -10.0,
+            overlap: 0.0,
+            scale: 1.0,
+            overview: false,
+        };
+        assert!(poster_sheets(&spec, 500.0, 500.0).is_err());
+    }
+
+    #[test]
+    fn test_mini_zine_slots() {
+        let sheet = mini_zine_sheet(100.0, 150.0);
+        let origins: Vec<(f32, f32)> = sheet.slots.iter().map(|s| (s.x, s.y)).collect();
+        assert_eq!(
+            origins,
+            vec![
+                (0.0, 150.0),
+                (100.0, 150.0),
+                (200.0, 150.0),
+                (300.0, 150.0),
+                (0.0, 0.0),
+                (100.0, 0.0),
+                (200.0, 0.0),
+                (300.0, 0.0),
+            ]
+        );
+        assert_eq!(
+            sheet.marks[0],
+            Mark::Cut {
+                from: (100.0, 150.0),
+                to: (300.0, 150.0)
+            }
+        );
+    }
+}
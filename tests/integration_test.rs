@@ -1,7 +1,15 @@
 use bookify_rs::{
-    args::{BaseOptions, BookletOptions, DoubleSidedOptions, FlipType, LayoutType, OddEven},
-    imposition::PdfImposer,
+    args::{
+        AnnotationMode, BaseOptions, Binding, BindingMethod, BlankPosition, BookletOptions,
+        ComposeOptions, DocumentStart, DoubleSidedOptions, Duplex, FlipType, FoldType, GridMode,
+        ImposeOptions, LayoutType, OddEven, PaperSize, SignatureSize,
+    },
+    calc::document_start_blanks,
+    imposition::{BookletSpec, PdfImposer},
+    metadata::{write_imposition, Job},
+    sheet::{CoverSpec, GridSpec, HandoutSpec, PosterSpec, SLUG_WIDTH},
 };
+use sha2::{Digest, Sha256};
 use std::fs;
 use std::path::PathBuf;
 
@@ -19,20 +27,1487 @@ fn test_booklet_imposition() {
     // Create booklet options
     let opts = BookletOptions {
         base: BaseOptions {
-            input: input_path.clone(),
+            input: vec![input_path.clone()],
+            input_list: None,
             output: Some(output_path.clone()),
             temp: false,
+            pages: None,
+            document_start: DocumentStart::Any,
+            reproducible: false,
+        },
+        impose: ImposeOptions { force: false },
+        compose: ComposeOptions {
+            annotations: AnnotationMode::Keep,
+        },
+        layout: LayoutType::TwoUp,
+        binding: None,
+        binding_method: BindingMethod::Saddle,
+        saddle_sheet_limit: 12,
+        signature_size: None,
+        marks: false,
+        blank_position: BlankPosition::End,
+        recto_chapters: false,
+        chapter_pages: None,
+        cover: None,
+        cover_pages: None,
+    };
+
+    // Execute booklet imposition
+    let mut imposer = PdfImposer::new(input_path).unwrap();
+    imposer
+        .export_booklet(&BookletSpec {
+            layout: opts.layout,
+            binding: opts.binding.unwrap_or(Binding::Ltr),
+            method: opts.binding_method,
+            signature_size: opts.signature_size,
+            saddle_sheet_limit: opts.saddle_sheet_limit,
+            marks: opts.marks,
+            blank_position: opts.blank_position.clone(),
+        })
+        .unwrap();
+    imposer.save(output_path.clone()).unwrap();
+
+    // Verify output file exists
+    assert!(output_path.exists());
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+#[test]
+fn test_booklet_imposition_rtl() {
+    let input_path = PathBuf::from(INPUT_PATH);
+    let output_path = PathBuf::from("tests/output/booklet-rtl-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    // Create right-to-left booklet options
+    let opts = BookletOptions {
+        base: BaseOptions {
+            input: vec![input_path.clone()],
+            input_list: None,
+            output: Some(output_path.clone()),
+            temp: false,
+            pages: None,
+            document_start: DocumentStart::Any,
+            reproducible: false,
+        },
+        impose: ImposeOptions { force: false },
+        compose: ComposeOptions {
+            annotations: AnnotationMode::Keep,
         },
         layout: LayoutType::TwoUp,
+        binding: Some(Binding::Rtl),
+        binding_method: BindingMethod::Saddle,
+        saddle_sheet_limit: 12,
+        signature_size: None,
+        marks: false,
+        blank_position: BlankPosition::End,
+        recto_chapters: false,
+        chapter_pages: None,
+        cover: None,
+        cover_pages: None,
+    };
+
+    // Sample document has no /Direction preference
+    let mut imposer = PdfImposer::new(input_path).unwrap();
+    assert_eq!(imposer.document_binding(), None);
+
+    // Execute booklet imposition
+    imposer
+        .export_booklet(&BookletSpec {
+            layout: opts.layout,
+            binding: opts.binding.unwrap(),
+            method: opts.binding_method,
+            signature_size: opts.signature_size,
+            saddle_sheet_limit: opts.saddle_sheet_limit,
+            marks: opts.marks,
+            blank_position: opts.blank_position.clone(),
+        })
+        .unwrap();
+    imposer.save(output_path.clone()).unwrap();
+
+    // Verify output file exists
+    assert!(output_path.exists());
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+#[test]
+fn test_booklet_imposition_top_binding() {
+    let input_path = PathBuf::from(INPUT_PATH);
+    let output_path = PathBuf::from("tests/output/booklet-top-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    // Pages are only stacked on composed sheets
+    let mut imposer = PdfImposer::new(input_path.clone()).unwrap();
+    assert!(imposer
+        .export_booklet(&BookletSpec::new(LayoutType::TwoUp, Binding::Top))
+        .is_err());
+
+    // Execute booklet imposition with top binding
+    let mut imposer = PdfImposer::new(input_path).unwrap();
+    imposer
+        .export_booklet(&BookletSpec {
+            marks: true,
+            ..BookletSpec::new(LayoutType::TwoUp, Binding::Top)
+        })
+        .unwrap();
+    imposer.save(output_path.clone()).unwrap();
+
+    // Portrait sheets with one page above and one below the fold at 18 + 421.1pt
+    let doc = lopdf::Document::load(&output_path).unwrap();
+    let pages = doc.get_pages();
+    let media_box = doc.get_dictionary(pages[&1]).unwrap().get(b"MediaBox");
+    let media_box: Vec<f32> = media_box
+        .and_then(lopdf::Object::as_array)
+        .unwrap()
+        .iter()
+        .map(|value| value.as_float().unwrap())
+        .collect();
+    assert!(media_box[3] > media_box[2]);
+    let content =
+        |page_num: u32| String::from_utf8(doc.get_page_content(pages[&page_num]).unwrap()).unwrap();
+    let front = content(3);
+    assert!(front.contains("1 0 0 1 18 439.1008 cm"));
+    assert!(front.contains("1 0 0 1 18 18 cm"));
+
+    // Back side of the sheet is turned upside down
+    let back = content(4);
+    assert!(back.contains("-1 0 0 -1 316.7539 860.2016 cm"));
+    assert!(back.contains("-1 0 0 -1 316.7539 439.1008 cm"));
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+#[test]
+fn test_booklet_imposition_mini_zine() {
+    let input_path = PathBuf::from(INPUT_PATH);
+    let output_path = PathBuf::from("tests/output/booklet-mini-zine-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    // Execute mini-zine imposition
+    let mut imposer = PdfImposer::new(input_path).unwrap();
+    imposer
+        .export_booklet(&BookletSpec::new(LayoutType::MiniZine, Binding::Ltr))
+        .unwrap();
+    imposer.save(output_path.clone()).unwrap();
+
+    // 23 pages fill 3 single-sided sheets of 8 pages
+    let doc = lopdf::Document::load(&output_path).unwrap();
+    assert_eq!(doc.get_pages().len(), 3);
+
+    // Top binding cannot be folded into a mini-zine
+    let mut imposer = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+    assert!(imposer
+        .export_booklet(&BookletSpec::new(LayoutType::MiniZine, Binding::Top))
+        .is_err());
+    let mut imposer = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+    assert!(imposer
+        .export_booklet(&BookletSpec {
+            method: BindingMethod::Perfect,
+            ..BookletSpec::new(LayoutType::MiniZine, Binding::Ltr)
+        })
+        .is_err());
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+#[test]
+fn test_booklet_imposition_binding_methods() {
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    for (method, name) in [
+        (BindingMethod::Perfect, "perfect"),
+        (BindingMethod::CutStack, "cut-stack"),
+    ] {
+        let output_path = PathBuf::from(format!("tests/output/booklet-{}-test.pdf", name));
+
+        // Execute booklet imposition
+        let mut imposer = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+        imposer
+            .export_booklet(&BookletSpec {
+                method,
+                ..BookletSpec::new(LayoutType::FourUp, Binding::Ltr)
+            })
+            .unwrap();
+        imposer.save(output_path.clone()).unwrap();
+
+        // 23 pages are padded to 3 sheets of 8 pages
+        let doc = lopdf::Document::load(&output_path).unwrap();
+        assert_eq!(doc.get_pages().len(), 24);
+
+        // Clean up test files
+        if DELETE_RESULT {
+            fs::remove_file(output_path).unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_booklet_imposition_signatures_with_marks() {
+    let input_path = PathBuf::from(INPUT_PATH);
+    let output_path = PathBuf::from("tests/output/booklet-signatures-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    // Execute booklet imposition in signatures of 2 sheets, with printer's marks
+    let mut imposer = PdfImposer::new(input_path).unwrap();
+    imposer
+        .export_booklet(&BookletSpec {
+            signature_size: Some(SignatureSize::Sheets(2)),
+            marks: true,
+            ..BookletSpec::new(LayoutType::TwoUp, Binding::Ltr)
+        })
+        .unwrap();
+    imposer.save(output_path.clone()).unwrap();
+
+    // 23 pages need 6 sheets, both sides are composed
+    let doc = lopdf::Document::load(&output_path).unwrap();
+    assert_eq!(doc.get_pages().len(), 12);
+
+    // Signatures require saddle stitching
+    let mut imposer = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+    assert!(imposer
+        .export_booklet(&BookletSpec {
+            method: BindingMethod::Perfect,
+            signature_size: Some(SignatureSize::Sheets(2)),
+            ..BookletSpec::new(LayoutType::TwoUp, Binding::Ltr)
+        })
+        .is_err());
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+#[test]
+fn test_unbooklet() {
+    let sheets_path = PathBuf::from("tests/output/unbooklet-sheets.pdf");
+    let output_path = PathBuf::from("tests/output/unbooklet-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    // Composed two-up sheets with an 18pt slug around the pages
+    let mut imposer = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+    imposer
+        .export_booklet(&BookletSpec {
+            marks: true,
+            ..BookletSpec::new(LayoutType::TwoUp, Binding::Ltr)
+        })
+        .unwrap();
+    imposer.save(sheets_path.clone()).unwrap();
+
+    let mut imposer = PdfImposer::new(sheets_path.clone()).unwrap();
+    imposer
+        .export_unbooklet(
+            LayoutType::TwoUp,
+            Binding::Ltr,
+            BindingMethod::Saddle,
+            &[],
+            SLUG_WIDTH,
+        )
+        .unwrap();
+    assert_eq!(imposer.total_pages(), 24);
+    let (width, height) = imposer.page_size();
+    assert!((width - 298.75).abs() < 0.01 && (height - 421.1).abs() < 0.01);
+    imposer.save(output_path.clone()).unwrap();
+
+    // Page 1 is the right half of the first side, page 2 the left half of the second side
+    let sheets = lopdf::Document::load(&sheets_path).unwrap();
+    let sides: Vec<_> = sheets.get_pages().into_values().collect();
+    let doc = lopdf::Document::load(&output_path).unwrap();
+    let pages: Vec<_> = doc.get_pages().into_values().collect();
+    let placed_side = |page| {
+        let (resources, _) = doc.get_page_resources(page);
+        let xobjects = resources
+            .unwrap()
+            .get(b"XObject")
+            .unwrap()
+            .as_dict()
+            .unwrap();
+        let form_id = xobjects.get(b"P0").unwrap().as_reference().unwrap();
+        let form = doc.get_object(form_id).unwrap().as_stream().unwrap();
+        form.decompressed_content()
+            .unwrap_or_else(|_| form.content.clone())
+    };
+    assert_eq!(
+        placed_side(pages[0]),
+        sheets.get_page_content(sides[0]).unwrap()
+    );
+    assert_eq!(
+        placed_side(pages[1]),
+        sheets.get_page_content(sides[1]).unwrap()
+    );
+    let content = String::from_utf8(doc.get_page_content(pages[0]).unwrap()).unwrap();
+    assert!(content.contains("1 0 0 1 -316.7539 -18 cm"));
+
+    // Sheets have two sides
+    let mut imposer = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+    assert!(imposer
+        .export_unbooklet(
+            LayoutType::TwoUp,
+            Binding::Ltr,
+            BindingMethod::Saddle,
+            &[],
+            0.0
+        )
+        .is_err());
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(sheets_path).unwrap();
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+#[test]
+fn test_unbooklet_recorded_binding() {
+    let sheets_path = PathBuf::from("tests/output/unbooklet-binding-sheets.pdf");
+    let output_path = PathBuf::from("tests/output/unbooklet-binding-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    // Side holding a page: 4,1 | 2,3 | 8,5 for perfect binding, 8,1 | 2,7 for a 2-sheet
+    // signature, while a single signature of 6 sheets holds page 8 on its eighth side
+    for (method, signature_size, page, side) in [
+        (BindingMethod::Perfect, None, 4, 0),
+        (BindingMethod::CutStack, None, 2, 1),
+        (BindingMethod::Saddle, Some(SignatureSize::Sheets(2)), 8, 0),
+        (BindingMethod::Saddle, None, 8, 7),
+    ] {
+        let mut imposer = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+        imposer
+            .export_booklet(&BookletSpec {
+                method,
+                signature_size,
+                marks: true,
+                ..BookletSpec::new(LayoutType::TwoUp, Binding::Ltr)
+            })
+            .unwrap();
+        imposer.save(sheets_path.clone()).unwrap();
+
+        let mut imposer = PdfImposer::new(sheets_path.clone()).unwrap();
+        let imposition = imposer.imposition().unwrap().clone();
+        imposer
+            .export_unbooklet(
+                LayoutType::TwoUp,
+                Binding::Ltr,
+                imposition.method.unwrap(),
+                &imposition.signatures,
+                SLUG_WIDTH,
+            )
+            .unwrap();
+        assert_eq!(imposer.total_pages(), 24);
+        imposer.save(output_path.clone()).unwrap();
+
+        // Each piece places one sheet side, found by its content
+        let sheets = lopdf::Document::load(&sheets_path).unwrap();
+        let sides: Vec<_> = sheets.get_pages().into_values().collect();
+        let doc = lopdf::Document::load(&output_path).unwrap();
+        let pieces: Vec<_> = doc.get_pages().into_values().collect();
+        let (resources, _) = doc.get_page_resources(pieces[page - 1]);
+        let form_id = resources
+            .unwrap()
+            .get(b"XObject")
+            .and_then(|xobjects| xobjects.as_dict())
+            .and_then(|xobjects| xobjects.get(b"P0"))
+            .and_then(lopdf::Object::as_reference)
+            .unwrap();
+        let form = doc.get_object(form_id).unwrap().as_stream().unwrap();
+        let content = form
+            .decompressed_content()
+            .unwrap_or_else(|_| form.content.clone());
+        assert_eq!(content, sheets.get_page_content(sides[side]).unwrap());
+    }
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(sheets_path).unwrap();
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+#[test]
+fn test_imposition_metadata() {
+    let booklet_path = PathBuf::from("tests/output/metadata-booklet.pdf");
+    let grid_path = PathBuf::from("tests/output/metadata-grid.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    let imposer = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+    assert!(imposer.imposition().is_none());
+    imposer.ensure_not_imposed().unwrap();
+
+    // Booklets record how they were imposed and are refused afterwards
+    let mut imposer = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+    imposer
+        .export_booklet(&BookletSpec {
+            marks: true,
+            ..BookletSpec::new(LayoutType::FourUp, Binding::Rtl)
+        })
+        .unwrap();
+    imposer.save(booklet_path.clone()).unwrap();
+    let mut imposer = PdfImposer::new(booklet_path.clone()).unwrap();
+    let imposition = imposer.imposition().unwrap();
+    assert_eq!(imposition.kind, "booklet");
+    assert_eq!(imposition.layout, Some(LayoutType::FourUp));
+    assert_eq!(imposition.binding, Some(Binding::Rtl));
+    assert_eq!(imposition.method, Some(BindingMethod::Saddle));
+    assert_eq!(imposition.signatures, vec![3]);
+    assert_eq!(imposition.source_pages, 23);
+    assert!(imposition.sheets);
+    let message = imposer.ensure_not_imposed().unwrap_err().to_string();
+    assert!(message.contains("unbooklet"));
+
+    // Undoing the imposition clears the record
+    imposer
+        .export_unbooklet(
+            LayoutType::FourUp,
+            Binding::Rtl,
+            BindingMethod::Saddle,
+            &[],
+            SLUG_WIDTH,
+        )
+        .unwrap();
+    assert!(imposer.imposition().is_none());
+
+    // Pages that only place other pages look imposed without a record
+    let spec = GridSpec {
+        columns: 2,
+        rows: 2,
+        gutter: 0.0,
+        margin: 0.0,
+        sheet: None,
+        cut_marks: false,
+    };
+    let mut imposer = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+    imposer
+        .export_grid(&spec, GridMode::Sequence, Duplex::None)
+        .unwrap();
+    imposer.save(grid_path.clone()).unwrap();
+    let mut doc = lopdf::Document::load(&grid_path).unwrap();
+    write_imposition(&mut doc, None).unwrap();
+    doc.save(&grid_path).unwrap();
+    let imposer = PdfImposer::new(grid_path.clone()).unwrap();
+    assert!(imposer.imposition().is_none());
+    assert!(imposer.ensure_not_imposed().is_err());
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(booklet_path).unwrap();
+        fs::remove_file(grid_path).unwrap();
+    }
+}
+
+#[test]
+fn test_provenance_metadata() {
+    let output_path = PathBuf::from("tests/output/provenance-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    let mut imposer = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+    imposer.set_job(Job {
+        command: "double-sided".to_string(),
+        parameters: vec![
+            ("flip-type".to_string(), "rr".to_string()),
+            ("odd-even".to_string(), "odd".to_string()),
+        ],
+    });
+    imposer
+        .export_double_sided(FlipType::RR, OddEven::Odd)
+        .unwrap();
+    imposer.save(output_path.clone()).unwrap();
+
+    let doc = lopdf::Document::load(&output_path).unwrap();
+    let info_id = doc.trailer.get(b"Info").unwrap().as_reference().unwrap();
+    let info = doc.get_dictionary(info_id).unwrap();
+    let text = |key: &[u8]| String::from_utf8(info.get(key).unwrap().as_str().unwrap().to_vec());
+    let source_hash = format!("{:x}", Sha256::digest(fs::read(INPUT_PATH).unwrap()));
+    assert!(text(b"Producer").unwrap().starts_with("bookify-rs "));
+    assert_eq!(text(b"BookifyCommand").unwrap(), "double-sided");
+    assert_eq!(
+        text(b"BookifyParameters").unwrap(),
+        "flip-type=rr; odd-even=odd"
+    );
+    assert_eq!(
+        text(b"BookifySources").unwrap(),
+        format!("sample.pdf (sha256 {})", source_hash)
+    );
+
+    // XMP packet in the catalog carries the same job
+    let metadata_id = doc
+        .catalog()
+        .unwrap()
+        .get(b"Metadata")
+        .unwrap()
+        .as_reference()
+        .unwrap();
+    let metadata = doc.get_object(metadata_id).unwrap().as_stream().unwrap();
+    assert_eq!(
+        metadata.dict.get(b"Subtype").unwrap().as_name().unwrap(),
+        b"XML"
+    );
+    let xmp = String::from_utf8(metadata.content.clone()).unwrap();
+    assert!(xmp.contains("<bookify:Command>double-sided</bookify:Command>"));
+    assert!(xmp.contains("<rdf:li>flip-type=rr</rdf:li>"));
+    assert!(xmp.contains(&format!("<bookify:SHA256>{}</bookify:SHA256>", source_hash)));
+    assert!(xmp.contains("<pdf:Producer>bookify-rs "));
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+#[test]
+fn test_reproducible_output() {
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    let save = |name: &str| {
+        let output_path = PathBuf::from(format!("tests/output/{}", name));
+        let mut imposer = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+        imposer.set_reproducible(true);
+        imposer
+            .export_double_sided(FlipType::RR, OddEven::Odd)
+            .unwrap();
+        imposer.save(output_path.clone()).unwrap();
+        let bytes = fs::read(&output_path).unwrap();
+        if DELETE_RESULT {
+            fs::remove_file(output_path).unwrap();
+        }
+        bytes
+    };
+    let first = save("reproducible-test-1.pdf");
+    let second = save("reproducible-test-2.pdf");
+    assert_eq!(first, second);
+
+    // The trailer /ID is derived from the content, not copied from the input
+    let doc = lopdf::Document::load_mem(&first).unwrap();
+    let id = doc.trailer.get(b"ID").unwrap().as_array().unwrap();
+    assert_eq!(id.len(), 2);
+    assert_eq!(id[0].as_str().unwrap(), id[1].as_str().unwrap());
+    assert_eq!(id[0].as_str().unwrap().len(), 16);
+}
+
+#[test]
+fn test_reproducible_cover_output() {
+    let cover_path = PathBuf::from("tests/output/reproducible-cover-input.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    let mut cover = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+    cover.select_pages(&"1-4".parse().unwrap()).unwrap();
+    cover.save(cover_path.clone()).unwrap();
+
+    // A separate cover file is made reproducible like the booklet itself
+    let run = |name: &str| {
+        let output_path = PathBuf::from(format!("tests/output/{}.pdf", name));
+        let status = std::process::Command::new(env!("CARGO_BIN_EXE_bookify_rs"))
+            .args([
+                "booklet",
+                INPUT_PATH,
+                "--marks",
+                "--reproducible",
+                "--cover",
+            ])
+            .arg(&cover_path)
+            .arg("-o")
+            .arg(&output_path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let cover_output = output_path.with_extension("cover.pdf");
+        let bytes = fs::read(&cover_output).unwrap();
+        if DELETE_RESULT {
+            fs::remove_file(output_path).unwrap();
+            fs::remove_file(cover_output).unwrap();
+        }
+        bytes
+    };
+    let first = run("reproducible-cover-1");
+    // Wall clock timestamps would differ from one second to the next
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    let second = run("reproducible-cover-2");
+    assert_eq!(first, second);
+
+    // The cover records the job of the booklet
+    let doc = lopdf::Document::load_mem(&first).unwrap();
+    let info_id = doc.trailer.get(b"Info").unwrap().as_reference().unwrap();
+    let info = doc.get_dictionary(info_id).unwrap();
+    let command = info.get(b"BookifyCommand").unwrap().as_str().unwrap();
+    assert_eq!(command, b"booklet");
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(cover_path).unwrap();
+    }
+}
+
+#[test]
+fn test_grid_imposition() {
+    let input_path = PathBuf::from(INPUT_PATH);
+    let output_path = PathBuf::from("tests/output/grid-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    // 2x2 grid of consecutive pages on A3 with mirrored backs
+    let spec = GridSpec {
+        columns: 2,
+        rows: 2,
+        gutter: 10.0,
+        margin: 20.0,
+        sheet: Some("a3".parse::<PaperSize>().unwrap()),
+        cut_marks: true,
+    };
+    let mut imposer = PdfImposer::new(input_path).unwrap();
+    imposer
+        .export_grid(&spec, GridMode::Sequence, Duplex::LongEdge)
+        .unwrap();
+    imposer.save(output_path.clone()).unwrap();
+
+    // 12 fronts fill 3 sheets, each followed by its back
+    let doc = lopdf::Document::load(&output_path).unwrap();
+    assert_eq!(doc.get_pages().len(), 6);
+
+    // Sample pages do not fit 5 rows on A5
+    let spec = GridSpec {
+        rows: 5,
+        sheet: Some("a5".parse::<PaperSize>().unwrap()),
+        ..spec
+    };
+    let mut imposer = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+    assert!(imposer
+        .export_grid(&spec, GridMode::Repeat, Duplex::None)
+        .is_err());
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+#[test]
+fn test_flashcards_imposition() {
+    let input_path = PathBuf::from(INPUT_PATH);
+    let output_path = PathBuf::from("tests/output/flashcards-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    // Sample document is used both for questions and answers
+    let spec = GridSpec {
+        columns: 2,
+        rows: 2,
+        gutter: 0.0,
+        margin: 20.0,
+        sheet: None,
+        cut_marks: true,
+    };
+    let mut imposer = PdfImposer::new(input_path.clone()).unwrap();
+    imposer
+        .export_flashcards(input_path.clone(), &spec, Duplex::ShortEdge)
+        .unwrap();
+    imposer.save(output_path.clone()).unwrap();
+
+    // 23 cards fill 6 sheets, fronts and backs
+    let doc = lopdf::Document::load(&output_path).unwrap();
+    assert_eq!(doc.get_pages().len(), 12);
+
+    // Flashcards need a back side
+    let mut imposer = PdfImposer::new(input_path.clone()).unwrap();
+    assert!(imposer
+        .export_flashcards(input_path, &spec, Duplex::None)
+        .is_err());
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+#[test]
+fn test_poster_imposition() {
+    let input_path = PathBuf::from(INPUT_PATH);
+    let output_path = PathBuf::from("tests/output/poster-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    // A6 pages enlarged 4 times (A2) on A4 sheets
+    let spec = PosterSpec {
+        sheet: "a4".parse().unwrap(),
+        margin: 20.0,
+        overlap: 20.0,
+        scale: 4.0,
+        overview: true,
+    };
+    let mut imposer = PdfImposer::new(input_path).unwrap();
+    imposer.export_poster(&spec).unwrap();
+    imposer.save(output_path.clone()).unwrap();
+
+    // Landscape sheets need 2 columns and 4 rows, 1 tile less than portrait ones
+    let doc = lopdf::Document::load(&output_path).unwrap();
+    assert_eq!(doc.get_pages().len(), 23 * 9);
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+#[test]
+fn test_brochure_imposition() {
+    let input_path = PathBuf::from(INPUT_PATH);
+    let output_path = PathBuf::from("tests/output/brochure-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    let sheet: PaperSize = "a4-landscape".parse().unwrap();
+    let mut imposer = PdfImposer::new(input_path.clone()).unwrap();
+    imposer
+        .export_brochure(FoldType::GateFold, sheet, 6.0, true)
+        .unwrap();
+    imposer.save(output_path.clone()).unwrap();
+
+    // 23 pages make 3 gate-fold brochures, outside and inside
+    let doc = lopdf::Document::load(&output_path).unwrap();
+    assert_eq!(doc.get_pages().len(), 6);
+
+    // Tuck wider than a panel
+    let mut imposer = PdfImposer::new(input_path).unwrap();
+    assert!(imposer
+        .export_brochure(FoldType::TriFold, sheet, 300.0, false)
+        .is_err());
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+#[test]
+fn test_handout_imposition() {
+    let input_path = PathBuf::from(INPUT_PATH);
+    let output_path = PathBuf::from("tests/output/handout-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    let spec = HandoutSpec {
+        slides: 6,
+        sheet: "a4".parse().unwrap(),
+        margin: 40.0,
+        notes: true,
+        header: "Sample".to_string(),
     };
+    let mut imposer = PdfImposer::new(input_path).unwrap();
+    imposer.export_handout(&spec).unwrap();
+    imposer.save(output_path.clone()).unwrap();
+
+    // 23 slides, 6 per page
+    let doc = lopdf::Document::load(&output_path).unwrap();
+    assert_eq!(doc.get_pages().len(), 4);
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+#[test]
+fn test_cover_pages_split_off() {
+    let input_path = PathBuf::from(INPUT_PATH);
+    let output_path = PathBuf::from("tests/output/cover-body-test.pdf");
+    let cover_path = PathBuf::from("tests/output/cover-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
 
-    // Execute booklet imposition
     let mut imposer = PdfImposer::new(input_path).unwrap();
-    imposer.export_booklet(opts.layout).unwrap();
+    let mut cover = imposer.split_off_pages(&[1, 2, 22, 23]).unwrap();
+    assert_eq!(cover.total_pages(), 4);
+    assert_eq!(imposer.total_pages(), 19);
+
+    // Body of 19 pages fills 3 four-up sheets, the cover 1 two-up sheet
+    imposer
+        .export_booklet(&BookletSpec::new(LayoutType::FourUp, Binding::Ltr))
+        .unwrap();
     imposer.save(output_path.clone()).unwrap();
+    cover
+        .export_booklet(&BookletSpec {
+            marks: true,
+            ..BookletSpec::new(LayoutType::TwoUp, Binding::Ltr)
+        })
+        .unwrap();
+    cover.save(cover_path.clone()).unwrap();
 
-    // Verify output file exists
-    assert!(output_path.exists());
+    let doc = lopdf::Document::load(&output_path).unwrap();
+    assert_eq!(doc.get_pages().len(), 24);
+    let doc = lopdf::Document::load(&cover_path).unwrap();
+    assert_eq!(doc.get_pages().len(), 2);
+
+    // Pages can only be taken once
+    assert!(imposer.split_off_pages(&[1, 1]).is_err());
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(output_path).unwrap();
+        fs::remove_file(cover_path).unwrap();
+    }
+}
+
+#[test]
+fn test_cover_spread() {
+    let input_path = PathBuf::from(INPUT_PATH);
+    let output_path = PathBuf::from("tests/output/cover-spread-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    // Art made of the first 3 pages of the sample
+    let mut art = PdfImposer::new(input_path.clone())
+        .unwrap()
+        .split_off_pages(&[1, 2, 3])
+        .unwrap();
+
+    let spec = CoverSpec {
+        page_width: 298.0,
+        page_height: 421.0,
+        spine_width: 20.0,
+        binding: Binding::Ltr,
+        spine_text: Some("Sample".to_string()),
+        marks: true,
+    };
+    art.export_cover_spread(&spec).unwrap();
+    art.save(output_path.clone()).unwrap();
+
+    let doc = lopdf::Document::load(&output_path).unwrap();
+    assert_eq!(doc.get_pages().len(), 1);
+
+    // Whole sample is not cover art
+    let mut imposer = PdfImposer::new(input_path).unwrap();
+    assert!(imposer.export_cover_spread(&spec).is_err());
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+#[test]
+fn test_blank_position_before_last() {
+    let output_path = PathBuf::from("tests/output/blank-before-last-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    // 23 pages padded to 24, the blank goes before the back cover
+    let source = lopdf::Document::load(INPUT_PATH).unwrap();
+    let source_pages: Vec<_> = source.get_pages().into_values().collect();
+    let mut imposer = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+    imposer
+        .export_booklet(&BookletSpec {
+            blank_position: BlankPosition::BeforeLast,
+            ..BookletSpec::new(LayoutType::TwoUp, Binding::Ltr)
+        })
+        .unwrap();
+    imposer.save(output_path.clone()).unwrap();
+
+    // Outside of the first sheet: back cover and front cover
+    let doc = lopdf::Document::load(&output_path).unwrap();
+    let pages: Vec<_> = doc.get_pages().into_values().collect();
+    assert_eq!(pages[0], source_pages[22]);
+    assert_eq!(pages[1], source_pages[0]);
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+#[test]
+fn test_recto_chapters_from_outline() {
+    let outlined_path = PathBuf::from("tests/output/outline-test.pdf");
+    let output_path = PathBuf::from("tests/output/recto-chapters-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    // Sample with chapters on pages 1, 4 and 9
+    let mut doc = lopdf::Document::load(INPUT_PATH).unwrap();
+    let pages = doc.get_pages();
+    for (title, page) in [("One", 1), ("Two", 4), ("Three", 9)] {
+        let bookmark = lopdf::Bookmark::new(title.to_string(), [0.0; 3], 0, pages[&page]);
+        doc.add_bookmark(bookmark, None);
+    }
+    let outline_id = doc.build_outline().unwrap();
+    doc.catalog_mut()
+        .unwrap()
+        .set("Outlines", lopdf::Object::Reference(outline_id));
+    doc.save(&outlined_path).unwrap();
+
+    let mut imposer = PdfImposer::new(outlined_path).unwrap();
+    assert_eq!(imposer.outline_chapter_starts(), vec![1, 4, 9]);
+
+    // Page 4 moves to 5, page 9 then lands on 10 and moves to 11
+    assert_eq!(imposer.pad_chapters_to_recto(&[1, 4, 9]).unwrap(), 2);
+    assert_eq!(imposer.total_pages(), 25);
+    imposer.save(output_path.clone()).unwrap();
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+/// Sample with an outline on pages 1, 4 and 9, a named destination on page 9
+/// and page labels i, ii, then 1, 2, ...
+fn navigation_sample(path: &PathBuf) {
+    let mut doc = lopdf::Document::load(INPUT_PATH).unwrap();
+    let pages = doc.get_pages();
+    for (title, page) in [("One", 1), ("Two", 4), ("Three", 9)] {
+        let bookmark = lopdf::Bookmark::new(title.to_string(), [0.0; 3], 0, pages[&page]);
+        doc.add_bookmark(bookmark, None);
+    }
+    let outline_id = doc.build_outline().unwrap();
+
+    let dest = |page: u32| {
+        lopdf::Object::Array(vec![
+            lopdf::Object::Reference(pages[&page]),
+            lopdf::Object::Name(b"XYZ".to_vec()),
+            10.into(),
+            400.into(),
+            lopdf::Object::Null,
+        ])
+    };
+    let mut dests = lopdf::Dictionary::new();
+    dests.set("intro", dest(2));
+    dests.set("appendix", dest(9));
+    let mut roman = lopdf::Dictionary::new();
+    roman.set("S", lopdf::Object::Name(b"r".to_vec()));
+    let mut decimal = lopdf::Dictionary::new();
+    decimal.set("S", lopdf::Object::Name(b"D".to_vec()));
+    let mut labels = lopdf::Dictionary::new();
+    labels.set(
+        "Nums",
+        vec![
+            0.into(),
+            lopdf::Object::Dictionary(roman),
+            2.into(),
+            lopdf::Object::Dictionary(decimal),
+        ],
+    );
+
+    let catalog = doc.catalog_mut().unwrap();
+    catalog.set("Outlines", lopdf::Object::Reference(outline_id));
+    catalog.set("Dests", dests);
+    catalog.set("PageLabels", labels);
+    doc.save(path).unwrap();
+}
+
+/// Page of each top-level outline item and named destination
+fn navigation_targets(doc: &lopdf::Document) -> (Vec<lopdf::ObjectId>, Vec<Vec<u8>>) {
+    let target = |item: &lopdf::Dictionary| {
+        let action = item.get(b"A").unwrap();
+        let (_, action) = doc.dereference(action).unwrap();
+        let dest = action.as_dict().unwrap().get(b"D").unwrap();
+        dest.as_array().unwrap()[0].as_reference().unwrap()
+    };
+    let outlines = doc
+        .get_dictionary(
+            doc.catalog()
+                .unwrap()
+                .get(b"Outlines")
+                .unwrap()
+                .as_reference()
+                .unwrap(),
+        )
+        .unwrap();
+    let mut items = Vec::new();
+    let mut next = outlines.get(b"First").ok();
+    while let Some(id) = next {
+        let item = doc.get_dictionary(id.as_reference().unwrap()).unwrap();
+        items.push(target(item));
+        next = item.get(b"Next").ok();
+    }
+    assert_eq!(
+        outlines.get(b"Count").unwrap().as_i64().unwrap(),
+        items.len() as i64
+    );
+
+    let names = doc
+        .catalog()
+        .unwrap()
+        .get(b"Dests")
+        .unwrap()
+        .as_dict()
+        .unwrap()
+        .iter()
+        .map(|(name, _)| name.clone())
+        .collect();
+    (items, names)
+}
+
+#[test]
+fn test_navigation_remapping() {
+    let navigation_path = PathBuf::from("tests/output/navigation-test.pdf");
+    let selected_path = PathBuf::from("tests/output/navigation-selected-test.pdf");
+    let booklet_path = PathBuf::from("tests/output/navigation-booklet-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+    navigation_sample(&navigation_path);
+
+    // Pages 8 to 1: the chapter on page 9 and its destination are gone
+    let mut imposer = PdfImposer::new(navigation_path.clone()).unwrap();
+    imposer.select_pages(&"8-1".parse().unwrap()).unwrap();
+    imposer.save(selected_path.clone()).unwrap();
+
+    let doc = lopdf::Document::load(&selected_path).unwrap();
+    let pages = doc.get_pages();
+    let (items, names) = navigation_targets(&doc);
+    assert_eq!(items, vec![pages[&8], pages[&5]]);
+    assert_eq!(names, vec![b"intro".to_vec()]);
+
+    // Labels follow their pages: 6, 5, 4, 3, 2, 1, ii, i
+    let labels = doc
+        .catalog()
+        .unwrap()
+        .get(b"PageLabels")
+        .unwrap()
+        .as_dict()
+        .unwrap()
+        .get(b"Nums")
+        .unwrap()
+        .as_array()
+        .unwrap();
+    assert_eq!(labels.len(), 16);
+    assert_eq!(labels[0].as_i64().unwrap(), 0);
+    let first = labels[1].as_dict().unwrap();
+    assert_eq!(first.get(b"S").unwrap().as_name().unwrap(), b"D");
+    assert_eq!(first.get(b"St").unwrap().as_i64().unwrap(), 6);
+
+    // On two-up sheets, destinations go to the sheet holding the page
+    let mut imposer = PdfImposer::new(navigation_path.clone()).unwrap();
+    imposer
+        .export_booklet(&BookletSpec {
+            marks: true,
+            ..BookletSpec::new(LayoutType::TwoUp, Binding::Ltr)
+        })
+        .unwrap();
+    imposer.save(booklet_path.clone()).unwrap();
+
+    let doc = lopdf::Document::load(&booklet_path).unwrap();
+    let sheets = doc.get_pages();
+    let (items, names) = navigation_targets(&doc);
+    // 24 pages on 12 sides: 24|1, 2|23, 22|3, 4|21, ..., 16|9
+    assert_eq!(items, vec![sheets[&1], sheets[&4], sheets[&9]]);
+    assert_eq!(names, vec![b"intro".to_vec(), b"appendix".to_vec()]);
+    assert!(doc.catalog().unwrap().get(b"PageLabels").is_err());
+
+    // Page 9 sits on the right half of its side, the point moves with it
+    let dests = doc
+        .catalog()
+        .unwrap()
+        .get(b"Dests")
+        .unwrap()
+        .as_dict()
+        .unwrap();
+    let appendix = dests.get(b"appendix").unwrap().as_array().unwrap();
+    assert_eq!(appendix[0].as_reference().unwrap(), sheets[&9]);
+    assert_eq!(appendix[1].as_name().unwrap(), b"XYZ");
+    let media_box = doc
+        .get_dictionary(sheets[&9])
+        .unwrap()
+        .get(b"MediaBox")
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .clone();
+    assert!(appendix[2].as_float().unwrap() > media_box[2].as_float().unwrap() / 2.0);
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(navigation_path).unwrap();
+        fs::remove_file(selected_path).unwrap();
+        fs::remove_file(booklet_path).unwrap();
+    }
+}
+
+/// Sample with a comment and a link to page 3 on page 1
+fn annotated_sample(path: &PathBuf) {
+    let mut doc = lopdf::Document::load(INPUT_PATH).unwrap();
+    let pages = doc.get_pages();
+    let rect = |values: [i64; 4]| lopdf::Object::Array(values.map(lopdf::Object::from).to_vec());
+
+    let mut appearance_dict = lopdf::Dictionary::new();
+    appearance_dict.set("BBox", rect([0, 0, 20, 20]));
+    let appearance = lopdf::Stream::new(appearance_dict, b"0 0 20 20 re f".to_vec());
+    let appearance_id = doc.add_object(appearance);
+    let mut appearances = lopdf::Dictionary::new();
+    appearances.set("N", lopdf::Object::Reference(appearance_id));
+    let mut comment = lopdf::Dictionary::new();
+    comment.set("Type", lopdf::Object::Name(b"Annot".to_vec()));
+    comment.set("Subtype", lopdf::Object::Name(b"Text".to_vec()));
+    comment.set("Rect", rect([100, 300, 120, 320]));
+    comment.set("Contents", lopdf::Object::string_literal("Check this"));
+    comment.set("AP", appearances);
+
+    let mut link = lopdf::Dictionary::new();
+    link.set("Type", lopdf::Object::Name(b"Annot".to_vec()));
+    link.set("Subtype", lopdf::Object::Name(b"Link".to_vec()));
+    link.set("Rect", rect([50, 50, 150, 70]));
+    link.set(
+        "Dest",
+        vec![
+            lopdf::Object::Reference(pages[&3]),
+            lopdf::Object::Name(b"Fit".to_vec()),
+        ],
+    );
+
+    let annots = vec![
+        lopdf::Object::Reference(doc.add_object(comment)),
+        lopdf::Object::Reference(doc.add_object(link)),
+    ];
+    doc.get_dictionary_mut(pages[&1])
+        .unwrap()
+        .set("Annots", annots);
+    doc.save(path).unwrap();
+}
+
+#[test]
+fn test_sheet_annotations() {
+    let annotated_path = PathBuf::from("tests/output/annotated-test.pdf");
+    let output_path = PathBuf::from("tests/output/sheet-annotations-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+    annotated_sample(&annotated_path);
+
+    let impose = |annotations: AnnotationMode| {
+        let mut imposer = PdfImposer::new(annotated_path.clone()).unwrap();
+        imposer.set_annotations(annotations);
+        imposer
+            .export_booklet(&BookletSpec {
+                marks: true,
+                ..BookletSpec::new(LayoutType::TwoUp, Binding::Ltr)
+            })
+            .unwrap();
+        imposer.save(output_path.clone()).unwrap();
+        lopdf::Document::load(&output_path).unwrap()
+    };
+    // Page 1 is alone on side 1, next to the padding blank page
+    let page_matrix = |doc: &lopdf::Document| {
+        let content = doc.get_page_content(doc.get_pages()[&1]).unwrap();
+        let content = lopdf::content::Content::decode(&content).unwrap();
+        let cm: Vec<f32> = content
+            .operations
+            .iter()
+            .find(|operation| operation.operator == "cm")
+            .unwrap()
+            .operands
+            .iter()
+            .map(|value| value.as_float().unwrap())
+            .collect();
+        cm
+    };
+
+    let doc = impose(AnnotationMode::Keep);
+    let sheets = doc.get_pages();
+    let annots = doc
+        .get_dictionary(sheets[&1])
+        .unwrap()
+        .get(b"Annots")
+        .unwrap()
+        .as_array()
+        .unwrap();
+    assert_eq!(annots.len(), 2);
+    let comment = doc
+        .get_dictionary(annots[0].as_reference().unwrap())
+        .unwrap();
+    assert_eq!(
+        comment.get(b"P").unwrap().as_reference().unwrap(),
+        sheets[&1]
+    );
+    let m = page_matrix(&doc);
+    let rect: Vec<f32> = comment
+        .get(b"Rect")
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|value| value.as_float().unwrap())
+        .collect();
+    assert!((rect[0] - (m[0] * 100.0 + m[4])).abs() < 0.01);
+    assert!((rect[3] - (m[3] * 320.0 + m[5])).abs() < 0.01);
+    // The link now goes to the sheet holding page 3
+    let link = doc
+        .get_dictionary(annots[1].as_reference().unwrap())
+        .unwrap();
+    let dest = link.get(b"Dest").unwrap().as_array().unwrap();
+    assert_eq!(dest[0].as_reference().unwrap(), sheets[&3]);
+
+    // Flattened, the comment is drawn on the sheet and the link is gone
+    let doc = impose(AnnotationMode::Flatten);
+    let sheet = doc.get_pages()[&1];
+    assert!(doc.get_dictionary(sheet).unwrap().get(b"Annots").is_err());
+    let content = String::from_utf8_lossy(&doc.get_page_content(sheet).unwrap()).into_owned();
+    assert!(content.contains("/P0A0 Do"));
+
+    let doc = impose(AnnotationMode::Drop);
+    let sheet = doc.get_pages()[&1];
+    assert!(doc.get_dictionary(sheet).unwrap().get(b"Annots").is_err());
+    let content = String::from_utf8_lossy(&doc.get_page_content(sheet).unwrap()).into_owned();
+    assert!(!content.contains("A0 Do"));
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(annotated_path).unwrap();
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+#[test]
+fn test_booklet_imposition_auto_signatures() {
+    let input_path = PathBuf::from(INPUT_PATH);
+    let output_path = PathBuf::from("tests/output/booklet-auto-signatures-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    // 6 two-up sheets in signatures of at most 4: 3+3
+    let mut imposer = PdfImposer::new(input_path).unwrap();
+    let signatures = imposer
+        .export_booklet(&BookletSpec {
+            signature_size: Some(SignatureSize::Auto),
+            saddle_sheet_limit: 4,
+            marks: true,
+            ..BookletSpec::new(LayoutType::TwoUp, Binding::Ltr)
+        })
+        .unwrap();
+    assert_eq!(signatures, vec![3, 3]);
+    imposer.save(output_path.clone()).unwrap();
+
+    // Second signature starts on the fourth sheet, with its collation mark
+    let doc = lopdf::Document::load(&output_path).unwrap();
+    let pages = doc.get_pages();
+    assert_eq!(pages.len(), 12);
+    let content = String::from_utf8_lossy(&doc.get_page_content(pages[&7]).unwrap()).into_owned();
+    assert!(content.contains("Signature 2/2 - Sheet 4/6 - Side A"));
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+#[test]
+fn test_page_selection() {
+    let output_path = PathBuf::from("tests/output/page-selection-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    let source = lopdf::Document::load(INPUT_PATH).unwrap();
+    let source_pages: Vec<_> = source.get_pages().into_values().collect();
+
+    // Last 3 pages reversed, then page 1 twice
+    let mut imposer = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+    imposer
+        .select_pages(&"end-r3,1,1".parse().unwrap())
+        .unwrap();
+    assert_eq!(imposer.total_pages(), 5);
+    imposer.save(output_path.clone()).unwrap();
+
+    let doc = lopdf::Document::load(&output_path).unwrap();
+    let pages: Vec<_> = doc.get_pages().into_values().collect();
+    assert_eq!(
+        pages[..4],
+        [
+            source_pages[22],
+            source_pages[21],
+            source_pages[20],
+            source_pages[0]
+        ]
+    );
+    // Repeated page is a copy with the same content
+    assert_ne!(pages[4], source_pages[0]);
+    assert_eq!(
+        doc.get_page_content(pages[4]).unwrap(),
+        doc.get_page_content(pages[3]).unwrap()
+    );
+
+    // Selection outside the document
+    let mut imposer = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+    assert!(imposer.select_pages(&"20-30".parse().unwrap()).is_err());
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+#[test]
+fn test_merge_documents() {
+    let output_path = PathBuf::from("tests/output/merge-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    let source = lopdf::Document::load(INPUT_PATH).unwrap();
+    let source_pages: Vec<_> = source.get_pages().into_values().collect();
+
+    // Second copy starts on an odd page: 23 pages, a blank, then 23 pages
+    let mut imposer = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+    let blanks = document_start_blanks(imposer.total_pages(), DocumentStart::Odd);
+    assert_eq!(blanks, 1);
+    imposer.append_blank_pages(blanks).unwrap();
+    assert_eq!(
+        imposer.append_document(PathBuf::from(INPUT_PATH)).unwrap(),
+        23
+    );
+    assert_eq!(imposer.total_pages(), 47);
+    imposer.save(output_path.clone()).unwrap();
+
+    let doc = lopdf::Document::load(&output_path).unwrap();
+    let pages: Vec<_> = doc.get_pages().into_values().collect();
+    assert_eq!(pages.len(), 47);
+    assert_eq!(pages[0], source_pages[0]);
+    assert!(doc.get_page_content(pages[23]).unwrap().is_empty());
+    assert_eq!(
+        doc.get_page_content(pages[24]).unwrap(),
+        source.get_page_content(source_pages[0]).unwrap()
+    );
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+#[test]
+fn test_interleave() {
+    let fronts_path = PathBuf::from("tests/output/interleave-fronts.pdf");
+    let backs_path = PathBuf::from("tests/output/interleave-backs.pdf");
+    let output_path = PathBuf::from("tests/output/interleave-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    let source = lopdf::Document::load(INPUT_PATH).unwrap();
+    let source_contents: Vec<_> = source
+        .get_pages()
+        .into_values()
+        .map(|page| source.get_page_content(page).unwrap())
+        .collect();
+
+    // Scan fronts in order and backs last page first, without the blank back of the last sheet
+    let mut fronts = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+    fronts
+        .export_double_sided(FlipType::NR, OddEven::Odd)
+        .unwrap();
+    fronts.save(fronts_path.clone()).unwrap();
+    let mut backs = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+    backs.select_pages(&"even".parse().unwrap()).unwrap();
+    backs.select_pages(&"end-1".parse().unwrap()).unwrap();
+    assert_eq!(backs.total_pages(), 11);
+    backs.save(backs_path.clone()).unwrap();
+
+    let mut imposer = PdfImposer::new(fronts_path.clone()).unwrap();
+    imposer
+        .export_interleave(backs_path.clone(), FlipType::NR)
+        .unwrap();
+    assert_eq!(imposer.total_pages(), 23);
+    imposer.save(output_path.clone()).unwrap();
+
+    let doc = lopdf::Document::load(&output_path).unwrap();
+    let contents: Vec<_> = doc
+        .get_pages()
+        .into_values()
+        .map(|page| doc.get_page_content(page).unwrap())
+        .collect();
+    assert_eq!(contents, source_contents);
+
+    // Backs that do not match the fronts
+    let mut imposer = PdfImposer::new(fronts_path.clone()).unwrap();
+    assert!(imposer
+        .export_interleave(PathBuf::from(INPUT_PATH), FlipType::NR)
+        .is_err());
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(fronts_path).unwrap();
+        fs::remove_file(backs_path).unwrap();
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
+#[test]
+fn test_page_operations() {
+    let output_path = PathBuf::from("tests/output/page-operations-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    let source = lopdf::Document::load(INPUT_PATH).unwrap();
+    let source_pages: Vec<_> = source.get_pages().into_values().collect();
+
+    // 23 pages in parts of 10
+    let imposer = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+    let parts = imposer.split_every(10).unwrap();
+    let sizes: Vec<u32> = parts.map(|part| part.unwrap().total_pages()).collect();
+    assert_eq!(sizes, vec![10, 10, 3]);
+
+    let mut imposer = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+    assert!(imposer.rotate_pages(&[1], 45).is_err());
+    assert!(imposer.insert_blank_pages(&[24]).is_err());
+    imposer.rotate_pages(&[2, 2], -90).unwrap();
+    imposer.insert_blank_pages(&[0, 23]).unwrap();
+    assert_eq!(imposer.total_pages(), 25);
+    imposer.save(output_path.clone()).unwrap();
+
+    let doc = lopdf::Document::load(&output_path).unwrap();
+    let pages: Vec<_> = doc.get_pages().into_values().collect();
+    assert!(doc.get_page_content(pages[0]).unwrap().is_empty());
+    assert_eq!(pages[1..24], source_pages[..]);
+    assert!(doc.get_page_content(pages[24]).unwrap().is_empty());
+    let rotation = |page| {
+        doc.get_dictionary(page)
+            .unwrap()
+            .get(b"Rotate")
+            .and_then(lopdf::Object::as_i64)
+            .unwrap()
+    };
+    assert_eq!(rotation(pages[1]), 0);
+    assert_eq!(rotation(pages[2]), 270);
 
     // Clean up test files
     if DELETE_RESULT {
@@ -51,10 +1526,15 @@ fn test_double_sided_imposition_odd() {
     // Create duplex printing options for odd pages
     let opts = DoubleSidedOptions {
         base: BaseOptions {
-            input: input_path.clone(),
+            input: vec![input_path.clone()],
+            input_list: None,
             output: Some(output_path.clone()),
             temp: false,
+            pages: None,
+            document_start: DocumentStart::Any,
+            reproducible: false,
         },
+        impose: ImposeOptions { force: false },
         flip_type: FlipType::RR,
         odd_even: OddEven::Odd,
     };
@@ -86,10 +1566,15 @@ fn test_double_sided_imposition_even() {
     // Create duplex printing options for even pages
     let opts = DoubleSidedOptions {
         base: BaseOptions {
-            input: input_path.clone(),
+            input: vec![input_path.clone()],
+            input_list: None,
             output: Some(output_path.clone()),
             temp: false,
+            pages: None,
+            document_start: DocumentStart::Any,
+            reproducible: false,
         },
+        impose: ImposeOptions { force: false },
         flip_type: FlipType::RR,
         odd_even: OddEven::Even,
     };
@@ -110,6 +1595,39 @@ fn test_double_sided_imposition_even() {
     }
 }
 
+#[test]
+fn test_double_sided_after_booklet() {
+    let booklet_path = PathBuf::from("tests/output/double-sided-booklet.pdf");
+    let output_path = PathBuf::from("tests/output/double-sided-booklet-test.pdf");
+
+    // Ensure output directory exists
+    fs::create_dir_all("tests/output").unwrap();
+
+    // Booklet pages in printer order are printed double-sided
+    let mut imposer = PdfImposer::new(PathBuf::from(INPUT_PATH)).unwrap();
+    imposer
+        .export_booklet(&BookletSpec::new(LayoutType::TwoUp, Binding::Ltr))
+        .unwrap();
+    imposer.save(booklet_path.clone()).unwrap();
+    let mut imposer = PdfImposer::new(booklet_path.clone()).unwrap();
+    imposer.ensure_not_double_sided().unwrap();
+    imposer
+        .export_double_sided(FlipType::RR, OddEven::Odd)
+        .unwrap();
+    imposer.save(output_path.clone()).unwrap();
+
+    // Its output is not reordered a second time
+    let imposer = PdfImposer::new(output_path.clone()).unwrap();
+    let message = imposer.ensure_not_double_sided().unwrap_err().to_string();
+    assert!(message.contains("double-sided"));
+
+    // Clean up test files
+    if DELETE_RESULT {
+        fs::remove_file(booklet_path).unwrap();
+        fs::remove_file(output_path).unwrap();
+    }
+}
+
 #[test]
 fn test_temp_output() {
     let input_path = PathBuf::from(INPUT_PATH);
@@ -117,16 +1635,44 @@ fn test_temp_output() {
     // Create temporary file options
     let opts = BookletOptions {
         base: BaseOptions {
-            input: input_path.clone(),
+            input: vec![input_path.clone()],
+            input_list: None,
             output: None,
             temp: true,
+            pages: None,
+            document_start: DocumentStart::Any,
+            reproducible: false,
+        },
+        impose: ImposeOptions { force: false },
+        compose: ComposeOptions {
+            annotations: AnnotationMode::Keep,
         },
         layout: LayoutType::TwoUp,
+        binding: None,
+        binding_method: BindingMethod::Saddle,
+        saddle_sheet_limit: 12,
+        signature_size: None,
+        marks: false,
+        blank_position: BlankPosition::End,
+        recto_chapters: false,
+        chapter_pages: None,
+        cover: None,
+        cover_pages: None,
     };
 
     // Execute booklet imposition and get temporary file path
     let mut imposer = PdfImposer::new(input_path).unwrap();
-    imposer.export_booklet(opts.layout).unwrap();
+    imposer
+        .export_booklet(&BookletSpec {
+            layout: opts.layout,
+            binding: opts.binding.unwrap_or(Binding::Ltr),
+            method: opts.binding_method,
+            signature_size: opts.signature_size,
+            saddle_sheet_limit: opts.saddle_sheet_limit,
+            marks: opts.marks,
+            blank_position: opts.blank_position.clone(),
+        })
+        .unwrap();
 
     // Create temporary file
     let temp_file = tempfile::Builder::new()
@@ -156,16 +1702,44 @@ fn test_custom_output_path() {
     // Create custom output path options
     let opts = BookletOptions {
         base: BaseOptions {
-            input: input_path.clone(),
+            input: vec![input_path.clone()],
+            input_list: None,
             output: Some(custom_output.clone()),
             temp: false,
+            pages: None,
+            document_start: DocumentStart::Any,
+            reproducible: false,
+        },
+        impose: ImposeOptions { force: false },
+        compose: ComposeOptions {
+            annotations: AnnotationMode::Keep,
         },
         layout: LayoutType::TwoUp,
+        binding: None,
+        binding_method: BindingMethod::Saddle,
+        saddle_sheet_limit: 12,
+        signature_size: None,
+        marks: false,
+        blank_position: BlankPosition::End,
+        recto_chapters: false,
+        chapter_pages: None,
+        cover: None,
+        cover_pages: None,
     };
 
     // Execute booklet imposition
     let mut imposer = PdfImposer::new(input_path).unwrap();
-    imposer.export_booklet(opts.layout).unwrap();
+    imposer
+        .export_booklet(&BookletSpec {
+            layout: opts.layout,
+            binding: opts.binding.unwrap_or(Binding::Ltr),
+            method: opts.binding_method,
+            signature_size: opts.signature_size,
+            saddle_sheet_limit: opts.saddle_sheet_limit,
+            marks: opts.marks,
+            blank_position: opts.blank_position.clone(),
+        })
+        .unwrap();
     imposer.save(custom_output.clone()).unwrap();
 
     // Verify custom output file exists